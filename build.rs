@@ -0,0 +1,47 @@
+//! Parses the embedded reports once so the app doesn't have to, see `src/dataset.rs`,
+//! and lists the stations in `SOURCES`, see `src/station.rs`.
+
+use std::{env, fmt::Write, fs, path::Path};
+
+#[allow(dead_code)]
+#[path = "src/dataset.rs"]
+mod dataset;
+#[allow(dead_code)]
+#[path = "src/noaa.rs"]
+mod noaa;
+
+/// The reports directory of every station by id, the first one is shown by default.
+/// The directories are under `assets` so the web build serves them too.
+const STATIONS: [(&str, &str); 1] = [("le-vigan", "assets/reports/raw")];
+
+fn main() {
+    let out = env::var("OUT_DIR").unwrap();
+    let mut sources = String::from("[\n");
+    for (id, dir) in STATIONS {
+        // `trunk` copies `assets/reports` into `dist/reports`
+        let remote = dir
+            .strip_prefix("assets/")
+            .and_then(|dir| dir.strip_suffix("/raw"))
+            .expect("the reports of a station are in `assets/…/raw`");
+        writeln!(
+            sources,
+            "    Source {{
+        id: {id:?},
+        dataset: include_bytes!(concat!(env!(\"OUT_DIR\"), \"/{id}.bin\")),
+        #[cfg(all(feature = \"fetch\", target_arch = \"wasm32\"))]
+        remote: {remote:?},
+    }},"
+        )
+        .unwrap();
+
+        println!("cargo:rerun-if-changed={dir}");
+        let dataset = dataset::read(Path::new(dir)).unwrap();
+        fs::write(
+            Path::new(&out).join(format!("{id}.bin")),
+            dataset::encode(&dataset),
+        )
+        .unwrap();
+    }
+    sources.push(']');
+    fs::write(Path::new(&out).join("sources.rs"), sources).unwrap();
+}
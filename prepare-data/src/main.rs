@@ -1,11 +1,51 @@
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 use meteo::Report;
 use scraper::{Html, Selector};
 
+// the app reads the reports from the same binary form, see `src/dataset.rs`
+#[allow(dead_code)]
+#[path = "../../src/dataset.rs"]
+mod dataset;
+#[allow(dead_code)]
+#[path = "../../src/noaa.rs"]
+mod noaa;
+
+/// The station shown by default in the app.
+const DEFAULT_SOURCE: (&str, &str) = (
+    "http://meteo.lyc-chamson-levigan.ac-montpellier.fr/meteo/",
+    "assets/reports/raw",
+);
+
+/// Downloads the reports of every station given as `<url> <directory>` pairs, or of
+/// the default station without arguments:
+///
+/// ```text
+/// cargo run -p prepare-data -- http://example.com/meteo/ assets/reports/example/raw
+/// ```
 #[tokio::main]
 async fn main() {
-    let base_url = "http://meteo.lyc-chamson-levigan.ac-montpellier.fr/meteo/";
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if args.len() % 2 != 0 {
+        eprintln!("Usage: prepare-data [<url> <directory>]...");
+        std::process::exit(1);
+    }
+    let sources: Vec<(&str, &str)> = if args.is_empty() {
+        vec![DEFAULT_SOURCE]
+    } else {
+        args.chunks(2)
+            .map(|pair| (pair[0].as_str(), pair[1].as_str()))
+            .collect()
+    };
+
+    for (base_url, dir) in sources {
+        prepare(base_url, Path::new(dir)).await;
+    }
+}
+
+/// Downloads the reports of a station in `dir`, and parses them into a dataset next
+/// to it for the web build.
+async fn prepare(base_url: &str, dir: &Path) {
     let main_page = format!("{base_url}?page=releve");
 
     println!("Downloading the main page at: {main_page}");
@@ -23,14 +63,16 @@ async fn main() {
 
     println!("Downloading all the reports");
 
+    tokio::fs::create_dir_all(dir).await.unwrap();
     let mut reports = Vec::new();
-    let mut read_dir = tokio::fs::read_dir("assets/reports/raw").await.unwrap();
+    let mut read_dir = tokio::fs::read_dir(dir).await.unwrap();
     while let Some(dir) = read_dir.next_entry().await.unwrap() {
         reports.push(dir.path());
     }
     let mut handles = Vec::new();
     for (name, url) in files {
         handles.push(tokio::spawn(handle_report(
+            dir.to_path_buf(),
             reports.clone(),
             name,
             url.to_string(),
@@ -43,11 +85,27 @@ async fn main() {
             // reports.push(report);
         }
     }
+
+    println!("Writing the dataset");
+    // the web build downloads it instead of every report
+    let dataset = dataset::read(dir).unwrap();
+    let dataset_path = dir
+        .parent()
+        .unwrap_or(Path::new("."))
+        .join(dataset::FILE_NAME);
+    tokio::fs::write(dataset_path, dataset::encode(&dataset))
+        .await
+        .unwrap();
 }
 
-async fn handle_report(reports: Vec<PathBuf>, name: String, url: String) -> Option<Report> {
+async fn handle_report(
+    dir: PathBuf,
+    reports: Vec<PathBuf>,
+    name: String,
+    url: String,
+) -> Option<Report> {
     let filename = PathBuf::from(sanitize(&name));
-    let path = PathBuf::from("assets/reports/raw/").join(filename);
+    let path = dir.join(filename);
     // We **always** wants to update the last two reports
     if !url.contains("NOAA") && reports.contains(&path) {
         return None;
@@ -0,0 +1,174 @@
+use std::collections::BTreeMap;
+
+use time::{Date, Duration};
+
+use crate::{
+    date_to_chart,
+    i18n::{text, Language, Text},
+};
+
+/// The size of the periods the daily values are grouped in.
+#[derive(Default, Debug, PartialEq, Clone, Copy)]
+pub enum Aggregation {
+    #[default]
+    Day,
+    Week,
+    Month,
+    Year,
+}
+
+impl Aggregation {
+    pub fn name(self) -> Text {
+        match self {
+            Aggregation::Day => text!("Jour"),
+            Aggregation::Week => text!("Semaine"),
+            Aggregation::Month => text!("Mois"),
+            Aggregation::Year => text!("Année"),
+        }
+    }
+
+    /// The first day of the period containing `date`.
+    pub fn start(self, date: Date) -> Date {
+        match self {
+            Aggregation::Day => date,
+            Aggregation::Week => {
+                date - Duration::days(date.weekday().number_days_from_monday() as i64)
+            }
+            Aggregation::Month => date.replace_day(1).unwrap(),
+            Aggregation::Year => Date::from_ordinal_date(date.year(), 1).unwrap(),
+        }
+    }
+
+    /// The first day of the period following the one starting at `start`.
+    pub fn end(self, start: Date) -> Date {
+        match self {
+            Aggregation::Day => start + Duration::DAY,
+            Aggregation::Week => start + Duration::WEEK,
+            Aggregation::Month => {
+                let (year, month) = match start.month().next() {
+                    time::Month::January => (start.year() + 1, time::Month::January),
+                    month => (start.year(), month),
+                };
+                Date::from_calendar_date(year, month, 1).unwrap()
+            }
+            Aggregation::Year => Date::from_ordinal_date(start.year() + 1, 1).unwrap(),
+        }
+    }
+
+    /// A human readable name for the period starting at `start`.
+    pub fn label(self, start: Date, lang: Language) -> String {
+        match self {
+            Aggregation::Day => lang.trf(text!("le {}"), &[&lang.date(start)]),
+            Aggregation::Week => lang.trf(text!("semaine du {}"), &[&lang.date(start)]),
+            Aggregation::Month => format!("{} {}", lang.month(start.month()), start.year()),
+            Aggregation::Year => start.year().to_string(),
+        }
+    }
+}
+
+/// The values of a period.
+#[derive(Debug, Clone, Copy)]
+pub struct Bucket {
+    pub start: Date,
+    pub end: Date,
+    pub count: usize,
+    pub sum: f64,
+    pub min: f64,
+    pub max: f64,
+}
+
+impl Bucket {
+    pub fn mean(&self) -> f64 {
+        self.sum / self.count as f64
+    }
+
+    /// Where the middle of the period is on a plot against the time.
+    pub fn center(&self) -> f64 {
+        (date_to_chart(self.start.midnight().assume_utc())
+            + date_to_chart(self.end.midnight().assume_utc()))
+            / 2.0
+    }
+
+    /// How long the period is on a plot against the time.
+    pub fn width(&self) -> f64 {
+        date_to_chart(self.end.midnight().assume_utc())
+            - date_to_chart(self.start.midnight().assume_utc())
+    }
+}
+
+/// How the values of a period are summed up in a single point.
+#[derive(Debug, Clone, Copy)]
+pub enum Reduce {
+    Min,
+    Mean,
+    Max,
+}
+
+impl Bucket {
+    pub fn reduce(&self, reduce: Reduce) -> f64 {
+        match reduce {
+            Reduce::Min => self.min,
+            Reduce::Mean => self.mean(),
+            Reduce::Max => self.max,
+        }
+    }
+}
+
+/// The points of a daily series once grouped per period, split in segments wherever
+/// days are missing. With [`Aggregation::Day`] the points are kept as is, they may
+/// not be at the middle of their day.
+pub fn level_of_detail(
+    points: impl Iterator<Item = (Date, [f64; 2])>,
+    aggregation: Aggregation,
+    reduce: Reduce,
+) -> Vec<Vec<[f64; 2]>> {
+    let mut segments: Vec<Vec<[f64; 2]>> = Vec::new();
+    // where the previous point's period ended
+    let mut end = None;
+
+    if aggregation == Aggregation::Day {
+        let mut points: Vec<_> = points.collect();
+        points.sort_by_key(|(date, _)| *date);
+        for (date, point) in points {
+            if end != Some(date) {
+                segments.push(Vec::new());
+            }
+            segments.last_mut().unwrap().push(point);
+            end = Some(date + Duration::DAY);
+        }
+        return segments;
+    }
+
+    for bucket in aggregate(points.map(|(date, [_, y])| (date, y)), aggregation) {
+        if end != Some(bucket.start) {
+            segments.push(Vec::new());
+        }
+        segments
+            .last_mut()
+            .unwrap()
+            .push([bucket.center(), bucket.reduce(reduce)]);
+        end = Some(bucket.end);
+    }
+    segments
+}
+
+/// Groups the daily values per period, sorted by date.
+pub fn aggregate(days: impl Iterator<Item = (Date, f64)>, aggregation: Aggregation) -> Vec<Bucket> {
+    let mut buckets: BTreeMap<Date, Bucket> = BTreeMap::new();
+    for (date, value) in days {
+        let start = aggregation.start(date);
+        let bucket = buckets.entry(start).or_insert(Bucket {
+            start,
+            end: aggregation.end(start),
+            count: 0,
+            sum: 0.0,
+            min: f64::INFINITY,
+            max: f64::NEG_INFINITY,
+        });
+        bucket.count += 1;
+        bucket.sum += value;
+        bucket.min = bucket.min.min(value);
+        bucket.max = bucket.max.max(value);
+    }
+    buckets.into_values().collect()
+}
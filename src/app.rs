@@ -1,74 +1,526 @@
-use std::cmp::Reverse;
+use std::{cmp::Reverse, collections::BTreeMap};
 
-use egui::{Layout, RichText};
-use include_dir::{include_dir, Dir};
+use egui::{ComboBox, Layout, RichText};
 
-use crate::{dashboard::Dashboard, inspect::InspectReports, report::Report};
+#[cfg(all(feature = "fetch", target_arch = "wasm32"))]
+use crate::fetch::FetchReports;
+use crate::{
+    calendar::Calendar,
+    dashboard::Dashboard,
+    date_from_chart,
+    diagnostics::Diagnostics,
+    i18n::{text, Language, Message, Text},
+    inspect::{InspectReports, InspectSession},
+    link::DeepLink,
+    merge,
+    noaa::StationInfo,
+    normals::Normals,
+    records::Records,
+    report::{DisplayReport, DisplaySession, Report},
+    station::{ReportFile, Source, StationComparison, SOURCES},
+    units::Units,
+};
 
 #[derive(Clone)]
 pub struct MeteoApp {
-    reports: Vec<Report>,
+    /// One per entry of [`SOURCES`], in the same order.
+    stations: Vec<Station>,
+    /// The station the views show.
+    station: usize,
 
     viewing: View,
+    language: Language,
+    units: Units,
+    calendar: Calendar,
+    station_comparison: StationComparison,
+
+    /// The fragment of the URL we last wrote.
+    #[cfg(target_arch = "wasm32")]
+    fragment: String,
+}
+
+/// The reports of a station and everything built from them.
+#[derive(Clone)]
+struct Station {
+    source: &'static Source,
+    info: StationInfo,
+    /// `None` when not a single report could be loaded.
+    data: Option<Data>,
+    diagnostics: Diagnostics,
+
+    /// The reports being downloaded, the embedded ones are shown in the meantime.
+    #[cfg(all(feature = "fetch", target_arch = "wasm32"))]
+    fetching: Option<FetchReports>,
+    /// Why the reports couldn't be downloaded.
+    #[cfg(all(feature = "fetch", target_arch = "wasm32"))]
+    fetch_error: Option<Message>,
+}
+
+/// Everything built from the reports.
+#[derive(Clone)]
+struct Data {
+    reports: Vec<Report>,
     dashboard: Dashboard,
     inspect_view: InspectReports,
+    records: Records,
+    normals: Normals,
 }
 
-#[derive(Default, Clone, Copy, PartialEq, Eq)]
-enum View {
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub enum View {
     #[default]
     Dashboard,
     Inspect,
+    Calendar,
+    Records,
+    Stations,
+    Diagnostics,
     About,
 }
 
-static REPORTS_DIR: Dir<'static> = include_dir!("assets/reports/raw");
+impl View {
+    const ALL: [View; 7] = [
+        View::Dashboard,
+        View::Inspect,
+        View::Calendar,
+        View::Records,
+        View::Stations,
+        View::Diagnostics,
+        View::About,
+    ];
 
-impl MeteoApp {
-    pub fn new() -> Self {
-        let dir = &REPORTS_DIR;
+    /// How the view is named in the URL.
+    pub fn slug(self) -> &'static str {
+        match self {
+            View::Dashboard => "globale",
+            View::Inspect => "rapports",
+            View::Calendar => "calendrier",
+            View::Records => "records",
+            View::Stations => "stations",
+            View::Diagnostics => "diagnostic",
+            View::About => "a-propos",
+        }
+    }
+
+    pub fn from_slug(slug: &str) -> Option<View> {
+        View::ALL.into_iter().find(|view| view.slug() == slug)
+    }
+
+    fn name(self) -> Text {
+        match self {
+            View::Dashboard => text!("Vue globale"),
+            View::Inspect => text!("Inspecter les rapports individuel"),
+            View::Calendar => text!("Calendrier"),
+            View::Records => text!("Records"),
+            View::Stations => text!("Comparer les stations"),
+            View::Diagnostics => text!("Diagnostic"),
+            View::About => text!("À propos"),
+        }
+    }
+}
+
+/// What is restored when the app is reopened, the reports themselves are always
+/// reloaded.
+#[derive(Default, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+struct Session {
+    viewing: View,
+    language: Language,
+    units: Units,
+    /// The id of the selected station.
+    station: Option<String>,
+    dashboard: DisplaySession,
+    inspect: InspectSession,
+}
+
+impl Station {
+    /// Keeps what could be loaded out of the files, the broken ones are reported in
+    /// the diagnostics.
+    fn load(
+        source: &'static Source,
+        info: StationInfo,
+        files: impl IntoIterator<Item = ReportFile>,
+    ) -> Self {
+        let mut diagnostics = Diagnostics::default();
         let mut reports = Vec::new();
-        for entry in dir.entries() {
-            if let Some(file) = entry.as_file() {
-                let original = file.contents_utf8().unwrap().to_string();
-                reports.push(Report::original(original))
+        for (name, report) in files {
+            match report {
+                Ok(report) if report.report.days.is_empty() => diagnostics.warning(
+                    name,
+                    Message::new(text!("Le rapport ne contient aucun jour"), &[]),
+                ),
+                Ok(report) => reports.push((name, report)),
+                Err(error) => diagnostics.error(name, error),
             }
         }
-        reports.sort_unstable_by_key(|report| Reverse(report.report.metadata.date));
-        reports.dedup_by_key(|report| report.report.metadata.date);
 
-        MeteoApp {
-            inspect_view: InspectReports::new(&reports),
-            dashboard: Dashboard::new(&reports),
-            viewing: View::default(),
-            reports,
+        // "mois en cours" and "01-2026" can be the same month downloaded twice
+        let mut months: BTreeMap<_, Vec<_>> = BTreeMap::new();
+        for (name, report) in reports {
+            months
+                .entry(Reverse({
+                    let date = report.report.metadata.date;
+                    (date.year(), date.month() as u8)
+                }))
+                .or_default()
+                .push((name, report));
+        }
+        let mut conflicts = Vec::new();
+        let unique: Vec<Report> = months
+            .into_values()
+            .map(|reports| merge::combine_month(reports, &mut conflicts))
+            .collect();
+        diagnostics.conflicts(conflicts);
+
+        let data = Dashboard::new(&unique, &mut diagnostics).map(|dashboard| Data {
+            inspect_view: InspectReports::new(&unique),
+            records: Records::new(&dashboard.report().report),
+            normals: Normals::new(dashboard.report().report.clone()),
+            dashboard,
+            reports: unique,
+        });
+
+        Station {
+            source,
+            info,
+            data,
+            diagnostics,
+            #[cfg(all(feature = "fetch", target_arch = "wasm32"))]
+            fetching: None,
+            #[cfg(all(feature = "fetch", target_arch = "wasm32"))]
+            fetch_error: None,
+        }
+    }
+
+    fn title(&self) -> String {
+        self.info.title(self.source)
+    }
+
+    fn session(&self) -> Session {
+        let mut session = Session {
+            station: Some(self.source.id.to_string()),
+            ..Session::default()
+        };
+        if let Some(data) = &self.data {
+            session.dashboard = data.dashboard.session();
+            session.inspect = data.inspect_view.session(&data.reports);
+        }
+        session
+    }
+
+    fn restore(&mut self, session: Session) {
+        if let Some(data) = &mut self.data {
+            data.dashboard.restore(session.dashboard);
+            data.inspect_view.restore(&data.reports, session.inspect);
+        }
+    }
+
+    /// Swaps the embedded reports for the downloaded ones once they're there, the
+    /// user stays where they were. The reports that couldn't be downloaded keep
+    /// their embedded version.
+    #[cfg(all(feature = "fetch", target_arch = "wasm32"))]
+    fn receive_reports(&mut self) {
+        let Some(result) = self.fetching.as_ref().and_then(FetchReports::poll) else {
+            return;
+        };
+        self.fetching = None;
+        match result {
+            Ok(dataset) => {
+                let (info, files) = self.source.downloaded(dataset);
+                let mut station = Self::load(self.source, info, files);
+                station.restore(self.session());
+                *self = station;
+            }
+            Err(error) => {
+                log::error!(
+                    "Could not download the reports of {}, using the embedded ones: {error}",
+                    self.source.id
+                );
+                self.fetch_error = Some(error);
+            }
+        }
+    }
+}
+
+impl MeteoApp {
+    /// Restores the previous session if there is one.
+    pub fn new(cc: &eframe::CreationContext<'_>) -> Self {
+        let mut app = Self::default();
+        let session = cc
+            .storage
+            .and_then(|storage| eframe::get_value::<Session>(storage, eframe::APP_KEY));
+        if let Some(session) = session {
+            app.restore(session);
+        }
+        #[cfg(all(feature = "fetch", target_arch = "wasm32"))]
+        for station in app.stations.iter_mut() {
+            station.fetching = Some(FetchReports::start(
+                cc.egui_ctx.clone(),
+                station.source.remote,
+            ));
+        }
+        app
+    }
+
+    fn current(&self) -> &Station {
+        &self.stations[self.station]
+    }
+
+    /// Selects the station with this id, unknown ids are ignored.
+    fn select_station(&mut self, id: &str) {
+        if let Some(index) = self.stations.iter().position(|s| s.source.id == id) {
+            self.station = index;
+        }
+    }
+
+    fn session(&self) -> Session {
+        Session {
+            viewing: self.viewing,
+            language: self.language,
+            units: self.units,
+            ..self.current().session()
+        }
+    }
+
+    fn restore(&mut self, session: Session) {
+        self.viewing = session.viewing;
+        self.language = session.language;
+        self.units = session.units;
+        if let Some(id) = &session.station {
+            self.select_station(id);
+        }
+        self.stations[self.station].restore(session);
+    }
+
+    /// Shows what a link shared by someone points at, it takes precedence over the
+    /// restored session.
+    pub fn with_link(mut self, link: DeepLink) -> Self {
+        if let Some(view) = link.view {
+            self.viewing = view;
+        }
+        if let Some(id) = &link.station {
+            self.select_station(id);
+        }
+        let Some(data) = &mut self.stations[self.station].data else {
+            return self;
+        };
+        let range = link.chart_range();
+        let apply = |displaying: &mut DisplayReport| {
+            if let Some(tab) = link.tab {
+                displaying.set_tab(tab);
+            }
+            if let Some(range) = range.clone() {
+                displaying.show_range(range);
+            }
+        };
+
+        if link.reports.is_empty() {
+            apply(data.dashboard.displaying_mut());
+        } else {
+            data.inspect_view.close_all();
+            for (year, month) in link.reports.iter() {
+                if let Some(displaying) = data.inspect_view.open_month(&data.reports, *year, *month)
+                {
+                    apply(displaying);
+                }
+            }
+        }
+        self
+    }
+
+    /// A link to what the user is looking at.
+    pub fn link(&self) -> DeepLink {
+        let mut link = DeepLink {
+            view: Some(self.viewing),
+            // the default station keeps the links short
+            station: (self.station != 0).then(|| self.current().source.id.to_string()),
+            ..DeepLink::default()
+        };
+        let Some(data) = &self.current().data else {
+            return link;
+        };
+        let displaying = match self.viewing {
+            View::Dashboard => Some(data.dashboard.displaying()),
+            View::Inspect => {
+                let opened: Vec<_> = data.inspect_view.opened(&data.reports).collect();
+                link.reports = opened
+                    .iter()
+                    .map(|(report, _)| {
+                        let date = report.report.metadata.date;
+                        (date.year(), date.month())
+                    })
+                    .collect();
+                // the windows can't all be at a different place in a link
+                opened.first().map(|(_, displaying)| *displaying)
+            }
+            View::Calendar | View::Records | View::Stations | View::Diagnostics | View::About => {
+                None
+            }
+        };
+        if let Some(displaying) = displaying {
+            link.tab = Some(displaying.tab());
+            link.range = displaying.visible().and_then(|range| {
+                Some((
+                    date_from_chart(*range.start())?.date(),
+                    date_from_chart(*range.end())?.date(),
+                ))
+            });
+        }
+        link
+    }
+
+    /// Keeps the URL pointing at what the user is looking at so it can be shared.
+    #[cfg(target_arch = "wasm32")]
+    fn update_fragment(&mut self, ctx: &egui::Context) {
+        // don't rewrite the URL on every frame while the user drags a plot
+        if ctx.input(|input| input.pointer.any_down()) {
+            return;
+        }
+        let fragment = self.link().to_fragment();
+        if fragment != self.fragment {
+            if let Some(window) = eframe::web_sys::window() {
+                // replacing the location doesn't fill the history of the browser
+                let _ = window.location().replace(&format!("#{fragment}"));
+            }
+            self.fragment = fragment;
         }
     }
 
     pub fn ui(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
+        let lang = self.language;
         egui::TopBottomPanel::top("top_panel").show(ctx, |ui| {
             egui::menu::bar(ui, |ui| {
-                ui.selectable_value(&mut self.viewing, View::Dashboard, "Vue globale");
-                ui.selectable_value(
-                    &mut self.viewing,
+                self.station_selector(ui);
+                ui.separator();
+                let views = [
+                    View::Dashboard,
                     View::Inspect,
-                    "Inspecter les rapports individuel",
-                );
+                    View::Calendar,
+                    View::Records,
+                    View::Stations,
+                ];
+                // there is nothing to compare until a second station is added
+                let comparable = self.stations.len() > 1;
+                for view in views {
+                    if view != View::Stations || comparable {
+                        ui.selectable_value(&mut self.viewing, view, lang.tr(view.name()));
+                    }
+                }
 
                 ui.with_layout(Layout::right_to_left(egui::Align::Center), |ui| {
                     egui::widgets::global_theme_preference_buttons(ui);
-                    ui.selectable_value(&mut self.viewing, View::About, "À propos");
+                    self.language.ui(ui);
+                    self.units.ui(ui);
+                    let about = lang.tr(View::About.name());
+                    ui.selectable_value(&mut self.viewing, View::About, about);
+                    let diagnostics = match self.current().diagnostics.len() {
+                        0 => String::from(lang.tr(View::Diagnostics.name())),
+                        problems => lang.trf(text!("⚠ Diagnostic ({})"), &[&problems]),
+                    };
+                    ui.selectable_value(&mut self.viewing, View::Diagnostics, diagnostics);
+                    #[cfg(all(feature = "fetch", target_arch = "wasm32"))]
+                    self.fetch_status(ui);
                 });
             });
         });
-        match self.viewing {
-            View::Dashboard => self.dashboard.ui(ctx),
-            View::Inspect => self.inspect_view.ui(&self.reports, ctx),
-            View::About => self.about(ctx),
+
+        // after the top bar so a new choice shows up right away
+        self.language.store(ctx);
+        self.units.store(ctx);
+        if self.viewing == View::Stations && self.stations.len() < 2 {
+            // a link or a session from a build with more stations
+            self.viewing = View::Dashboard;
+        }
+        let data = self.stations[self.station].data.as_mut();
+        match (self.viewing, data) {
+            (View::Stations, _) => self.compare_stations(ctx),
+            (View::Diagnostics, _) => self.current().diagnostics.ui(ctx),
+            (View::About, _) => self.about(ctx),
+            (_, None) => self.no_data(ctx),
+            (View::Dashboard, Some(data)) => data.dashboard.ui(&mut data.normals, ctx),
+            (View::Inspect, Some(data)) => {
+                data.inspect_view.ui(&data.reports, &mut data.normals, ctx)
+            }
+            (View::Calendar, Some(data)) => {
+                if let Some(date) = self.calendar.ui(&data.dashboard.report().report, ctx) {
+                    data.dashboard.focus(date);
+                    self.viewing = View::Dashboard;
+                }
+            }
+            (View::Records, Some(data)) => {
+                if let Some(date) = data.records.ui(ctx) {
+                    if data.inspect_view.open(&data.reports, date) {
+                        self.viewing = View::Inspect;
+                    }
+                }
+            }
+        }
+    }
+
+    fn station_selector(&mut self, ui: &mut egui::Ui) {
+        let current = self.current();
+        let response = ComboBox::from_id_salt("station")
+            .selected_text(format!("📍 {}", current.title()))
+            .show_index(ui, &mut self.station, self.stations.len(), |i| {
+                self.stations[i].title()
+            });
+        let location = self.current().info.location();
+        if !location.is_empty() {
+            response.on_hover_text(location);
+        }
+    }
+
+    /// The stations without any report can't be compared.
+    fn compare_stations(&mut self, ctx: &egui::Context) {
+        let mut selected = 0;
+        let mut stations = Vec::new();
+        for (i, station) in self.stations.iter().enumerate() {
+            if let Some(data) = &station.data {
+                if i == self.station {
+                    selected = stations.len();
+                }
+                stations.push((station.title(), &data.dashboard.report().report));
+            }
+        }
+        if self.current().data.is_none() {
+            return self.no_data(ctx);
+        }
+        self.station_comparison.ui(&stations, selected, ctx);
+    }
+
+    fn no_data(&self, ctx: &egui::Context) {
+        let lang = self.language;
+        egui::CentralPanel::default().show(ctx, |ui| {
+            ui.vertical_centered(|ui| {
+                ui.add_space(40.);
+                ui.heading(lang.tr(text!("Aucun rapport à afficher")));
+                ui.label(lang.tr(text!(
+                    "Les rapports météo n'ont pas pu être chargés, réessayez plus tard."
+                )));
+                if !self.current().diagnostics.is_empty() {
+                    ui.label(lang.tr(text!("Le diagnostic détaille ce qui n'a pas fonctionné.")));
+                }
+            });
+        });
+    }
+
+    #[cfg(all(feature = "fetch", target_arch = "wasm32"))]
+    fn fetch_status(&self, ui: &mut egui::Ui) {
+        let (station, lang) = (self.current(), self.language);
+        if station.fetching.is_some() {
+            ui.label(lang.tr(text!("Chargement des rapports…")));
+            ui.spinner();
+        } else if let Some(error) = &station.fetch_error {
+            ui.label(lang.tr(text!("⚠ Rapports hors ligne")))
+                .on_hover_text(lang.trf(
+                    text!("Les rapports n'ont pas pu être téléchargés : {}"),
+                    &[&error.tr(lang)],
+                ));
         }
     }
 
     fn about(&self, ctx: &egui::Context) {
+        let lang = self.language;
         egui::CentralPanel::default().show(ctx, |ui| {
             ui.vertical_centered(|ui| {
                 ui.set_max_width(500.);
@@ -80,21 +532,21 @@ impl MeteoApp {
 
                     let now = time::OffsetDateTime::now_utc();
 
-                    ui.label("Salut, je m'appelle");
+                    ui.label(lang.tr(text!("Salut, je m'appelle")));
                     ui.label(RichText::new("Thomas Campistron").strong());
-                    ui.label(", ou juste");
+                    ui.label(lang.tr(text!(", ou juste")));
                     ui.label(RichText::new("Tamo").strong());
-                    ui.label("sur internet. J'ai");
+                    ui.label(lang.tr(text!("sur internet. J'ai")));
 
                     let birthdate = time::OffsetDateTime::new_utc(time::Date::from_calendar_date(1996, time::Month::November, 21).unwrap(), time::Time::from_hms(0, 0, 0).unwrap());
                     let alive_since = now - birthdate;
                     let years = alive_since.whole_days() / 365;
-                    ui.label(years.to_string()).on_hover_ui(|ui| {ui.label(RichText::new("C'est jeune").small());});
+                    ui.label(years.to_string()).on_hover_ui(|ui| {ui.label(RichText::new(lang.tr(text!("C'est jeune"))).small());});
 
-                    ui.label("ans et je suis développeur pour");
+                    ui.label(lang.tr(text!("ans et je suis développeur pour")));
                     ui.hyperlink_to("Meilisearch", "https://meilisearch.com");
-                    ui.label("en télétravail. J'habite");
-                    ui.hyperlink_to("au Vigan", "https://fr.wikipedia.org/wiki/Le_Vigan_(Gard)");
+                    ui.label(lang.tr(text!("en télétravail. J'habite")));
+                    ui.hyperlink_to(lang.tr(text!("au Vigan")), "https://fr.wikipedia.org/wiki/Le_Vigan_(Gard)");
 
                     let now = time::OffsetDateTime::now_utc();
                     let moved = time::OffsetDateTime::new_utc(time::Date::from_calendar_date(2023, time::Month::June, 8).unwrap(), time::Time::from_hms(0, 0, 0).unwrap());
@@ -103,22 +555,22 @@ impl MeteoApp {
                     let months = (elapsed.whole_days() % 365) / 30;
 
                     match years {
-                        0 => ui.label(format!("depuis {months} mois")),
-                        1 => ui.label(format!("depuis {years} an et {months} mois")),
-                        _ => ui.label(format!("depuis {years} ans")),
+                        0 => ui.label(lang.trf(text!("depuis {} mois"), &[&months])),
+                        1 => ui.label(lang.trf(text!("depuis {} an et {} mois"), &[&years, &months])),
+                        _ => ui.label(lang.trf(text!("depuis {} ans"), &[&years])),
                     };
 
-                    ui.label("et j'ai fait ce site après avoir découvert que le lycée à côté de chez moi collecte des données météorologiques depuis 2006.");
-                    ui.label("Toutes les données affichées sur mon site viennent en réalité de :");
+                    ui.label(lang.tr(text!("et j'ai fait ce site après avoir découvert que le lycée à côté de chez moi collecte des données météorologiques depuis 2006.")));
+                    ui.label(lang.tr(text!("Toutes les données affichées sur mon site viennent en réalité de :")));
                     ui.hyperlink("http://meteo.lyc-chamson-levigan.ac-montpellier.fr/meteo/index.php?page=releve");
-                    ui.label("Elles sont mises à jour tous les jours à 2h du matin.");
+                    ui.label(lang.tr(text!("Elles sont mises à jour tous les jours à 2h du matin.")));
                 });
 
                 ui.add_space(20.);
                 ui.horizontal_wrapped(|ui| {
-                    ui.label("L'intégralité du code qui génère ce site web est disponible");
-                    ui.hyperlink_to("ici", "https://github.com/irevoire/egui-meteo");
-                    ui.label("où vous pouvez m'y faire des suggestions via les « issues ».");
+                    ui.label(lang.tr(text!("L'intégralité du code qui génère ce site web est disponible")));
+                    ui.hyperlink_to(lang.tr(text!("ici")), "https://github.com/irevoire/egui-meteo");
+                    ui.label(lang.tr(text!("où vous pouvez m'y faire des suggestions via les « issues ».")));
                 });
             });
         });
@@ -127,13 +579,40 @@ impl MeteoApp {
 
 impl Default for MeteoApp {
     fn default() -> Self {
-        Self::new()
+        MeteoApp {
+            stations: SOURCES
+                .iter()
+                .map(|source| {
+                    let (info, files) = source.embedded();
+                    Station::load(source, info, files)
+                })
+                .collect(),
+            station: 0,
+            viewing: View::default(),
+            language: Language::default(),
+            units: Units::default(),
+            calendar: Calendar::default(),
+            station_comparison: StationComparison::default(),
+            #[cfg(target_arch = "wasm32")]
+            fragment: String::new(),
+        }
     }
 }
 
 impl eframe::App for MeteoApp {
+    /// Called by the framework to save the session before shutdown, and regularly.
+    fn save(&mut self, storage: &mut dyn eframe::Storage) {
+        eframe::set_value(storage, eframe::APP_KEY, &self.session());
+    }
+
     /// Called each time the UI needs repainting, which may be many times per second.
     fn update(&mut self, ctx: &egui::Context, frame: &mut eframe::Frame) {
+        #[cfg(all(feature = "fetch", target_arch = "wasm32"))]
+        for station in self.stations.iter_mut() {
+            station.receive_reports();
+        }
         self.ui(ctx, frame);
+        #[cfg(target_arch = "wasm32")]
+        self.update_fragment(ctx);
     }
 }
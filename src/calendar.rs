@@ -0,0 +1,232 @@
+use egui::{Align2, Color32, Context, FontId, Pos2, Rect, Sense, Ui, Vec2};
+use time::{Date, Month};
+
+use crate::{
+    i18n::{text, Language, Text},
+    noaa,
+    normals::{day_of_year, DAYS_IN_YEAR},
+    plot::LEAP_YEAR,
+    report::day_details,
+    units::{Quantity, Units},
+};
+
+#[derive(Default, Debug, PartialEq, Clone, Copy)]
+pub enum Metric {
+    #[default]
+    MeanTemp,
+    HighTemp,
+    Rain,
+    HighWind,
+}
+
+impl Metric {
+    const ALL: [Metric; 4] = [
+        Metric::MeanTemp,
+        Metric::HighTemp,
+        Metric::Rain,
+        Metric::HighWind,
+    ];
+
+    fn name(self) -> Text {
+        match self {
+            Metric::MeanTemp => text!("Température moyenne"),
+            Metric::HighTemp => text!("Température maximale"),
+            Metric::Rain => text!("Pluie"),
+            Metric::HighWind => text!("Rafales"),
+        }
+    }
+
+    fn quantity(self) -> Quantity {
+        match self {
+            Metric::MeanTemp | Metric::HighTemp => Quantity::Temperature,
+            Metric::Rain => Quantity::Rain,
+            Metric::HighWind => Quantity::Wind,
+        }
+    }
+
+    fn value(self, report: &noaa::Report, index: usize) -> f64 {
+        let day = &report.days[index];
+        match self {
+            Metric::MeanTemp => day.mean_temp as f64,
+            Metric::HighTemp => day.high_temp as f64,
+            Metric::Rain => day.rain as f64,
+            Metric::HighWind => day.high_wind_speed as f64,
+        }
+    }
+
+    /// The color of a value between `min` and `max`.
+    fn color(self, value: f64, min: f64, max: f64) -> Color32 {
+        let t = if max > min {
+            ((value - min) / (max - min)).clamp(0.0, 1.0) as f32
+        } else {
+            0.0
+        };
+        match self {
+            Metric::MeanTemp | Metric::HighTemp => gradient(
+                &[
+                    Color32::from_rgb(40, 80, 200),
+                    Color32::from_rgb(240, 240, 240),
+                    Color32::from_rgb(200, 40, 40),
+                ],
+                t,
+            ),
+            // Most days have no or very little rain, without the square root
+            // everything but the storms would look the same.
+            Metric::Rain => gradient(
+                &[
+                    Color32::from_rgb(240, 240, 240),
+                    Color32::from_rgb(20, 60, 160),
+                ],
+                t.sqrt(),
+            ),
+            Metric::HighWind => gradient(
+                &[
+                    Color32::from_rgb(240, 240, 240),
+                    Color32::from_rgb(120, 40, 160),
+                ],
+                t,
+            ),
+        }
+    }
+}
+
+/// Linear interpolation between evenly spaced colors.
+fn gradient(stops: &[Color32], t: f32) -> Color32 {
+    let position = t * (stops.len() - 1) as f32;
+    let index = (position.floor() as usize).min(stops.len() - 2);
+    stops[index].lerp_to_gamma(stops[index + 1], position - index as f32)
+}
+
+/// A heatmap with one row per year and one cell per day.
+#[derive(Default, Debug, Clone)]
+pub struct Calendar {
+    metric: Metric,
+}
+
+impl Calendar {
+    /// Returns the date the user clicked on.
+    pub fn ui(&mut self, report: &noaa::Report, ctx: &Context) -> Option<Date> {
+        egui::CentralPanel::default()
+            .show(ctx, |ui| self.heatmap(report, ui))
+            .inner
+    }
+
+    fn heatmap(&mut self, report: &noaa::Report, ui: &mut Ui) -> Option<Date> {
+        let metric = self.metric;
+        let lang = Language::get(ui.ctx());
+        let units = Units::get(ui.ctx());
+        let (min, max) = (0..report.days.len())
+            .map(|index| metric.value(report, index))
+            .fold((f64::INFINITY, f64::NEG_INFINITY), |(min, max), value| {
+                (min.min(value), max.max(value))
+            });
+
+        ui.horizontal(|ui| {
+            for metric in Metric::ALL {
+                ui.selectable_value(&mut self.metric, metric, lang.tr(metric.name()));
+            }
+            ui.separator();
+            if min <= max {
+                ui.label(units.format(lang, metric.quantity(), min));
+                let (rect, _) = ui.allocate_exact_size(Vec2::new(150.0, 12.0), Sense::hover());
+                let steps = 50;
+                for step in 0..steps {
+                    let t = step as f64 / (steps - 1) as f64;
+                    let x = rect.left() + rect.width() * step as f32 / steps as f32;
+                    let cell = Rect::from_min_size(
+                        Pos2::new(x, rect.top()),
+                        Vec2::new(rect.width() / steps as f32, rect.height()),
+                    );
+                    let color = metric.color(min + t * (max - min), min, max);
+                    ui.painter().rect_filled(cell, 0.0, color);
+                }
+                ui.label(units.format(lang, metric.quantity(), max));
+            }
+        });
+        ui.separator();
+
+        let years = report.days.iter().map(|day| day.date.year());
+        let (Some(first), Some(last)) = (years.clone().min(), years.max()) else {
+            ui.label(lang.tr(text!("Aucune donnée à afficher")));
+            return None;
+        };
+
+        // The most recent year is at the top
+        let mut cells = vec![[None; DAYS_IN_YEAR]; (last - first + 1) as usize];
+        for (index, day) in report.days.iter().enumerate() {
+            let row = (last - day.date.year()) as usize;
+            if let Some(row) = cells.get_mut(row) {
+                row[day_of_year(day.date)] = Some(index);
+            }
+        }
+
+        let label_width = 40.0;
+        let header_height = 16.0;
+        let available = ui.available_size();
+        let cell_width = ((available.x - label_width) / DAYS_IN_YEAR as f32).max(1.0);
+        let row_height = ((available.y - header_height) / cells.len() as f32).clamp(4.0, 20.0);
+        let size = Vec2::new(
+            label_width + cell_width * DAYS_IN_YEAR as f32,
+            header_height + row_height * cells.len() as f32,
+        );
+        let (response, painter) = ui.allocate_painter(size, Sense::click());
+        let origin = response.rect.min + Vec2::new(label_width, header_height);
+        let text_color = ui.visuals().text_color();
+
+        for month in 1..=12 {
+            let month = Month::try_from(month).unwrap();
+            let slot = day_of_year(Date::from_calendar_date(LEAP_YEAR, month, 1).unwrap());
+            painter.text(
+                Pos2::new(origin.x + slot as f32 * cell_width, response.rect.top()),
+                Align2::LEFT_TOP,
+                lang.month(month).chars().take(3).collect::<String>(),
+                FontId::proportional(11.0),
+                text_color,
+            );
+        }
+
+        for (row, days) in cells.iter().enumerate() {
+            let y = origin.y + row as f32 * row_height;
+            painter.text(
+                Pos2::new(response.rect.left(), y + row_height / 2.0),
+                Align2::LEFT_CENTER,
+                (last - row as i32).to_string(),
+                FontId::proportional(row_height.min(12.0)),
+                text_color,
+            );
+            for (slot, index) in days.iter().enumerate() {
+                let Some(index) = index else { continue };
+                let cell = Rect::from_min_size(
+                    Pos2::new(origin.x + slot as f32 * cell_width, y),
+                    Vec2::new(cell_width, (row_height - 1.0).max(1.0)),
+                );
+                let color = metric.color(metric.value(report, *index), min, max);
+                painter.rect_filled(cell, 0.0, color);
+            }
+        }
+
+        let cell_at = |pos: Pos2| -> Option<usize> {
+            let offset = pos - origin;
+            if offset.x < 0.0 || offset.y < 0.0 {
+                return None;
+            }
+            let row = (offset.y / row_height) as usize;
+            let slot = (offset.x / cell_width) as usize;
+            *cells.get(row)?.get(slot)?
+        };
+
+        let clicked = if response.clicked() {
+            response
+                .interact_pointer_pos()
+                .and_then(cell_at)
+                .map(|index| report.days[index].date)
+        } else {
+            None
+        };
+        if let Some(index) = response.hover_pos().and_then(cell_at) {
+            response.on_hover_ui_at_pointer(|ui| day_details(ui, report, index));
+        }
+
+        clicked
+    }
+}
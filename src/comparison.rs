@@ -0,0 +1,155 @@
+use std::collections::BTreeSet;
+
+use egui::{ComboBox, Ui};
+use egui_plot::{AxisHints, Line};
+use time::Month;
+
+use crate::{
+    i18n::{text, Language, Text},
+    noaa,
+    normals::day_of_year,
+    plot::{create_plot_day_of_month, create_plot_day_of_year},
+    units::{Quantity, Units},
+};
+
+#[derive(Default, Debug, PartialEq, Clone, Copy)]
+pub enum Metric {
+    #[default]
+    Temperature,
+    Rain,
+    Wind,
+}
+
+impl Metric {
+    fn name(self) -> Text {
+        match self {
+            Metric::Temperature => text!("Température moyenne"),
+            Metric::Rain => text!("Pluie"),
+            Metric::Wind => text!("Rafales"),
+        }
+    }
+
+    fn quantity(self) -> Quantity {
+        match self {
+            Metric::Temperature => Quantity::Temperature,
+            Metric::Rain => Quantity::Rain,
+            Metric::Wind => Quantity::Wind,
+        }
+    }
+}
+
+/// What the x axis of the comparison shows.
+#[derive(Default, Debug, PartialEq, Clone, Copy)]
+pub enum Axis {
+    #[default]
+    DayOfYear,
+    DayOfMonth(Month),
+}
+
+/// Draws the same calendar period of several years on top of each other.
+#[derive(Default, Debug, Clone)]
+pub struct Comparison {
+    metric: Metric,
+    axis: Axis,
+    /// `None` until the user picks the years, we then show the last two years.
+    years: Option<BTreeSet<i32>>,
+}
+
+impl Comparison {
+    pub fn ui(&mut self, report: &noaa::Report, ui: &mut Ui) {
+        let available: BTreeSet<i32> = report.days.iter().map(|day| day.date.year()).collect();
+        let selected = self
+            .years
+            .get_or_insert_with(|| available.iter().rev().take(2).copied().collect());
+        let lang = Language::get(ui.ctx());
+
+        ui.horizontal(|ui| {
+            for metric in [Metric::Temperature, Metric::Rain, Metric::Wind] {
+                ui.selectable_value(&mut self.metric, metric, lang.tr(metric.name()));
+            }
+            ui.separator();
+            ui.selectable_value(
+                &mut self.axis,
+                Axis::DayOfYear,
+                lang.tr(text!("Jour de l'année")),
+            );
+            // like the day of the year, picking it again keeps the month shown
+            let by_month = matches!(self.axis, Axis::DayOfMonth(_));
+            if ui
+                .selectable_label(by_month, lang.tr(text!("Jour du mois")))
+                .clicked()
+                && !by_month
+            {
+                self.axis = Axis::DayOfMonth(report.last_date().month());
+            }
+            if let Axis::DayOfMonth(month) = &mut self.axis {
+                ComboBox::from_id_salt("comparison month")
+                    .selected_text(lang.month(*month))
+                    .show_ui(ui, |ui| {
+                        for m in 1..=12 {
+                            let m = Month::try_from(m).unwrap();
+                            ui.selectable_value(month, m, lang.month(m));
+                        }
+                    });
+            }
+        });
+        ui.horizontal_wrapped(|ui| {
+            ui.label(lang.tr(text!("Années :")));
+            for year in available.iter() {
+                let mut shown = selected.contains(year);
+                if ui.toggle_value(&mut shown, year.to_string()).changed() {
+                    if shown {
+                        selected.insert(*year);
+                    } else {
+                        selected.remove(year);
+                    }
+                }
+            }
+            if ui.button(lang.tr(text!("Aucune"))).clicked() {
+                selected.clear();
+            }
+        });
+        ui.separator();
+
+        let (metric, axis) = (self.metric, self.axis);
+        let units = Units::get(ui.ctx());
+        let quantity = metric.quantity();
+        let formatter = move |value: f64| units.format_plotted(lang, quantity, value);
+        let plot = match axis {
+            Axis::DayOfYear => create_plot_day_of_year("Comparaison annuelle", lang, formatter),
+            Axis::DayOfMonth(_) => {
+                create_plot_day_of_month("Comparaison mensuelle", lang, formatter)
+            }
+        }
+        .custom_y_axes(vec![AxisHints::new_y().label(lang.trf(
+            text!("{} en {}"),
+            &[&lang.tr(metric.name()), &units.symbol(quantity)],
+        ))]);
+        plot.show(ui, |ui| {
+            for year in selected.iter() {
+                let points: Vec<_> = report
+                    .days
+                    .iter()
+                    .filter(|day| day.date.year() == *year)
+                    .filter_map(|day| {
+                        let x = match axis {
+                            Axis::DayOfYear => day_of_year(day.date) as f64,
+                            Axis::DayOfMonth(month) if day.date.month() == month => {
+                                day.date.day() as f64
+                            }
+                            Axis::DayOfMonth(_) => return None,
+                        };
+                        let y = match metric {
+                            Metric::Temperature => day.mean_temp as f64,
+                            Metric::Rain => day.rain as f64,
+                            Metric::Wind => day.high_wind_speed as f64,
+                        };
+                        Some([x, units.convert(quantity, y)])
+                    })
+                    .collect();
+                let name = year.to_string();
+                ui.line(Line::new(&name, points).name(&name));
+            }
+        });
+    }
+}
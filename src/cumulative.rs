@@ -0,0 +1,85 @@
+use std::collections::BTreeMap;
+
+use crate::noaa;
+use crate::normals::{day_of_year, DAYS_IN_YEAR};
+
+/// The rain fallen since the 1st of January, for every day of a year.
+#[derive(Debug, Clone)]
+pub struct Year {
+    /// `None` before the first and after the last day we have data for.
+    pub totals: Vec<Option<f64>>,
+    /// Whether we have data for every day from the 1st of January to the 31st of
+    /// December, a missing day would drag the totals down.
+    pub complete: bool,
+}
+
+/// The year to date rain of every year in the history.
+#[derive(Debug, Clone)]
+pub struct YearToDate {
+    pub years: BTreeMap<i32, Year>,
+}
+
+impl YearToDate {
+    pub fn new(history: &noaa::Report) -> Self {
+        let mut daily: BTreeMap<i32, Vec<Option<f64>>> = BTreeMap::new();
+        for day in history.days.iter() {
+            let year = daily
+                .entry(day.date.year())
+                .or_insert_with(|| vec![None; DAYS_IN_YEAR]);
+            let slot = &mut year[day_of_year(day.date)];
+            *slot = Some(slot.unwrap_or(0.0) + day.rain as f64);
+        }
+
+        let years = daily
+            .into_iter()
+            .map(|(year, mut totals)| {
+                // the 29th of February only exists on leap years
+                let complete = totals.iter().enumerate().all(|(slot, value)| {
+                    value.is_some() || (slot == 59 && !time::util::is_leap_year(year))
+                });
+                let first = totals.iter().position(Option::is_some);
+                let last = totals.iter().rposition(Option::is_some);
+                let mut total = 0.0;
+                if let (Some(first), Some(last)) = (first, last) {
+                    // the missing days, including the 29th of February of the regular
+                    // years, count as dry days
+                    for value in &mut totals[first..=last] {
+                        total += value.unwrap_or(0.0);
+                        *value = Some(total);
+                    }
+                }
+                (year, Year { totals, complete })
+            })
+            .collect();
+
+        Self { years }
+    }
+
+    /// The minimum, mean and maximum year to date rain of the complete years.
+    pub fn envelope(&self) -> [Vec<[f64; 2]>; 3] {
+        let complete: Vec<_> = self.years.values().filter(|year| year.complete).collect();
+        let mut envelope = [Vec::new(), Vec::new(), Vec::new()];
+        if complete.is_empty() {
+            return envelope;
+        }
+
+        for slot in 0..DAYS_IN_YEAR {
+            let totals = complete.iter().filter_map(|year| year.totals[slot]);
+            let (mut min, mut max, mut sum, mut count) = (f64::INFINITY, f64::NEG_INFINITY, 0.0, 0);
+            for total in totals {
+                min = min.min(total);
+                max = max.max(total);
+                sum += total;
+                count += 1;
+            }
+            if count > 0 {
+                let x = slot as f64;
+                envelope[0].push([x, min]);
+                envelope[1].push([x, sum / count as f64]);
+                envelope[2].push([x, max]);
+            }
+        }
+
+        envelope
+    }
+}
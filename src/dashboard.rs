@@ -1,6 +1,11 @@
 use egui::Context;
+use time::Date;
 
-use crate::report::{DisplayReport, Report};
+use crate::{
+    diagnostics::Diagnostics,
+    normals::Normals,
+    report::{DisplayReport, DisplaySession, Report},
+};
 
 #[derive(Clone)]
 pub struct Dashboard {
@@ -9,18 +14,50 @@ pub struct Dashboard {
 }
 
 impl Dashboard {
-    pub fn new(reports: &[Report]) -> Self {
+    /// Returns `None` without any report.
+    pub fn new(reports: &[Report], diagnostics: &mut Diagnostics) -> Option<Self> {
         let mut reports = reports.iter();
-        let first_report = reports.next().unwrap().clone();
-        let maxi_report = reports.fold(first_report, |left, right| left.merge(right));
+        let mut maxi_report = reports.next()?.clone();
+        // merged in place so the growing report isn't copied for every month
+        let mut conflicts = Vec::new();
+        for report in reports {
+            maxi_report.merge(report, &mut conflicts);
+        }
+        diagnostics.conflicts(conflicts);
 
-        Self {
+        Some(Self {
             maxi_report,
             displaying: DisplayReport::default(),
-        }
+        })
+    }
+
+    pub fn report(&self) -> &Report {
+        &self.maxi_report
+    }
+
+    /// Moves the plots to the period around `date`.
+    pub fn focus(&mut self, date: Date) {
+        self.displaying.focus(date);
+    }
+
+    pub fn displaying(&self) -> &DisplayReport {
+        &self.displaying
+    }
+
+    pub fn displaying_mut(&mut self) -> &mut DisplayReport {
+        &mut self.displaying
+    }
+
+    pub fn session(&self) -> DisplaySession {
+        self.displaying.session()
+    }
+
+    pub fn restore(&mut self, session: DisplaySession) {
+        self.displaying.restore(session);
     }
 
-    pub fn ui(&mut self, ctx: &Context) {
-        egui::CentralPanel::default().show(ctx, |ui| self.displaying.ui(&self.maxi_report, ui));
+    pub fn ui(&mut self, normals: &mut Normals, ctx: &Context) {
+        egui::CentralPanel::default()
+            .show(ctx, |ui| self.displaying.ui(&self.maxi_report, normals, ui));
     }
 }
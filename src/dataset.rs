@@ -0,0 +1,361 @@
+//! The reports of a station, parsed once and stored in a compact binary form so the
+//! app doesn't parse hundreds of texts at startup. `build.rs` embeds one per station
+//! and `prepare-data` writes one next to the reports the web build downloads.
+//!
+//! This file is also compiled in the build script and in `prepare-data`, it must only
+//! depend on [`crate::noaa`], `time` and `std`.
+
+use std::{fs, path::Path};
+
+use time::{Date, OffsetDateTime, PrimitiveDateTime};
+
+use crate::noaa;
+
+/// Why a file of the reports directory couldn't be turned into a report.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Failure {
+    NotUtf8,
+    /// What the parser said.
+    Unreadable(String),
+}
+
+/// A file of the reports directory by name.
+pub type File = (String, Result<noaa::Report, Failure>);
+
+/// The name of the dataset `prepare-data` writes next to the `raw` directory of the
+/// reports of a station.
+#[allow(dead_code)] // not used by the build script
+pub const FILE_NAME: &str = "dataset.bin";
+
+/// Bumped whenever the layout changes, so the app doesn't read a dataset served by an
+/// older `prepare-data` as garbage.
+const VERSION: u8 = 1;
+
+/// What is stored for a station.
+#[derive(Default, Debug, Clone, PartialEq)]
+pub struct Dataset {
+    /// The header of the most recent report, the most likely to be up to date.
+    pub station: noaa::StationInfo,
+    /// Sorted by name.
+    pub files: Vec<File>,
+}
+
+/// Parses every file of a reports directory.
+#[allow(dead_code)] // only used by the build script and `prepare-data`
+pub fn read(dir: &Path) -> std::io::Result<Dataset> {
+    let mut files = Vec::new();
+    // the header of the most recent report is the most likely to be up to date
+    let mut newest: Option<(Date, String)> = None;
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        if !path.is_file() {
+            continue;
+        }
+        let name = path.file_name().unwrap().to_string_lossy().into_owned();
+        let report = match fs::read_to_string(&path) {
+            Ok(text) => {
+                let report = noaa::Report::parse(&text).map_err(Failure::Unreadable);
+                if let Ok(report) = &report {
+                    let date = report.metadata.date;
+                    if newest.as_ref().is_none_or(|(newest, _)| *newest < date) {
+                        newest = Some((date, text));
+                    }
+                }
+                report
+            }
+            Err(_) => Err(Failure::NotUtf8),
+        };
+        files.push((name, report));
+    }
+    // the order of `read_dir` changes from a machine to another
+    files.sort_by(|(a, _), (b, _)| a.cmp(b));
+    Ok(Dataset {
+        station: newest
+            .map(|(_, text)| noaa::StationInfo::parse(&text))
+            .unwrap_or_default(),
+        files,
+    })
+}
+
+#[allow(dead_code)] // only used by the build script, `prepare-data` and the tests
+pub fn encode(dataset: &Dataset) -> Vec<u8> {
+    let mut writer = Writer::default();
+    writer.u8(VERSION);
+    writer.station(&dataset.station);
+    writer.u32(dataset.files.len() as u32);
+    for (name, report) in &dataset.files {
+        writer.str(name);
+        match report {
+            Ok(report) => {
+                writer.u8(0);
+                writer.report(report);
+            }
+            Err(Failure::NotUtf8) => writer.u8(1),
+            Err(Failure::Unreadable(error)) => {
+                writer.u8(2);
+                writer.str(error);
+            }
+        }
+    }
+    writer.bytes
+}
+
+/// Reads back what [`encode`] wrote, `None` if it was written by another version or
+/// is cut short.
+pub fn decode(bytes: &[u8]) -> Option<Dataset> {
+    let mut reader = Reader { bytes };
+    if reader.u8()? != VERSION {
+        return None;
+    }
+    let station = reader.station()?;
+    let files = (0..reader.u32()?)
+        .map(|_| {
+            let name = reader.str()?;
+            let report = match reader.u8()? {
+                0 => Ok(reader.report()?),
+                1 => Err(Failure::NotUtf8),
+                _ => Err(Failure::Unreadable(reader.str()?)),
+            };
+            Some((name, report))
+        })
+        .collect::<Option<_>>()?;
+    Some(Dataset { station, files })
+}
+
+#[derive(Default)]
+struct Writer {
+    bytes: Vec<u8>,
+}
+
+#[allow(dead_code)] // only used by the build script and the tests
+impl Writer {
+    fn u8(&mut self, value: u8) {
+        self.bytes.push(value);
+    }
+
+    fn u32(&mut self, value: u32) {
+        self.bytes.extend(value.to_le_bytes());
+    }
+
+    fn f32(&mut self, value: f32) {
+        self.bytes.extend(value.to_le_bytes());
+    }
+
+    fn str(&mut self, value: &str) {
+        self.u32(value.len() as u32);
+        self.bytes.extend(value.as_bytes());
+    }
+
+    fn option_str(&mut self, value: Option<&str>) {
+        match value {
+            Some(value) => {
+                self.u8(1);
+                self.str(value);
+            }
+            None => self.u8(0),
+        }
+    }
+
+    fn date(&mut self, date: Date) {
+        self.bytes.extend(date.to_julian_day().to_le_bytes());
+    }
+
+    fn date_time(&mut self, at: PrimitiveDateTime) {
+        self.bytes
+            .extend(at.assume_utc().unix_timestamp().to_le_bytes());
+    }
+
+    /// The fields must be written in the order [`Reader::station`] reads them.
+    fn station(&mut self, station: &noaa::StationInfo) {
+        for field in [
+            &station.name,
+            &station.city,
+            &station.state,
+            &station.elevation,
+            &station.latitude,
+            &station.longitude,
+        ] {
+            self.option_str(field.as_deref());
+        }
+    }
+
+    /// The values must be written in the order [`Reader::report`] reads them.
+    fn report(&mut self, report: &noaa::Report) {
+        self.date(report.metadata.date);
+        self.u32(report.days.len() as u32);
+        for day in report.days.iter() {
+            self.date(day.date);
+            self.f32(day.mean_temp);
+            self.f32(day.high_temp);
+            self.date_time(day.high_temp_date);
+            self.f32(day.low_temp);
+            self.date_time(day.low_temp_date);
+            self.f32(day.heat_deg_days);
+            self.f32(day.cool_deg_days);
+            self.f32(day.rain);
+            self.f32(day.avg_wind_speed);
+            self.f32(day.high_wind_speed);
+            match day.high_wind_speed_date {
+                Some(at) => {
+                    self.u8(1);
+                    self.date_time(at);
+                }
+                None => self.u8(0),
+            }
+            self.str(&day.dom_dir);
+        }
+    }
+}
+
+/// Every read is `None` once the bytes are exhausted, a downloaded dataset can be
+/// cut short.
+struct Reader<'a> {
+    bytes: &'a [u8],
+}
+
+impl Reader<'_> {
+    fn take<const N: usize>(&mut self) -> Option<[u8; N]> {
+        let (head, tail) = self.bytes.split_first_chunk()?;
+        self.bytes = tail;
+        Some(*head)
+    }
+
+    fn u8(&mut self) -> Option<u8> {
+        self.take().map(u8::from_le_bytes)
+    }
+
+    fn u32(&mut self) -> Option<u32> {
+        self.take().map(u32::from_le_bytes)
+    }
+
+    fn f32(&mut self) -> Option<f32> {
+        self.take().map(f32::from_le_bytes)
+    }
+
+    fn str(&mut self) -> Option<String> {
+        let len = self.u32()? as usize;
+        let text = self.bytes.get(..len)?;
+        self.bytes = &self.bytes[len..];
+        Some(String::from_utf8_lossy(text).into_owned())
+    }
+
+    fn option_str(&mut self) -> Option<Option<String>> {
+        match self.u8()? {
+            1 => self.str().map(Some),
+            _ => Some(None),
+        }
+    }
+
+    fn date(&mut self) -> Option<Date> {
+        Date::from_julian_day(i32::from_le_bytes(self.take()?)).ok()
+    }
+
+    fn date_time(&mut self) -> Option<PrimitiveDateTime> {
+        let at = OffsetDateTime::from_unix_timestamp(i64::from_le_bytes(self.take()?)).ok()?;
+        Some(PrimitiveDateTime::new(at.date(), at.time()))
+    }
+
+    fn station(&mut self) -> Option<noaa::StationInfo> {
+        Some(noaa::StationInfo {
+            name: self.option_str()?,
+            city: self.option_str()?,
+            state: self.option_str()?,
+            elevation: self.option_str()?,
+            latitude: self.option_str()?,
+            longitude: self.option_str()?,
+        })
+    }
+
+    fn report(&mut self) -> Option<noaa::Report> {
+        let date = self.date()?;
+        let days = (0..self.u32()?)
+            .map(|_| {
+                Some(noaa::Day {
+                    date: self.date()?,
+                    mean_temp: self.f32()?,
+                    high_temp: self.f32()?,
+                    high_temp_date: self.date_time()?,
+                    low_temp: self.f32()?,
+                    low_temp_date: self.date_time()?,
+                    heat_deg_days: self.f32()?,
+                    cool_deg_days: self.f32()?,
+                    rain: self.f32()?,
+                    avg_wind_speed: self.f32()?,
+                    high_wind_speed: self.f32()?,
+                    high_wind_speed_date: match self.u8()? {
+                        1 => Some(self.date_time()?),
+                        _ => None,
+                    },
+                    dom_dir: self.str()?,
+                })
+            })
+            .collect::<Option<_>>()?;
+        Some(noaa::Report {
+            metadata: noaa::Metadata { date },
+            days,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use time::Month;
+
+    use super::*;
+    use crate::noaa::test;
+
+    #[test]
+    fn round_trip() {
+        let station = noaa::StationInfo {
+            name: Some(String::from("maxou")),
+            city: Some(String::from("LE VIGAN")),
+            latitude: Some(String::from("43° 59' 23\" N")),
+            ..noaa::StationInfo::default()
+        };
+        let files = vec![
+            (
+                String::from("09-2020"),
+                Ok(test::month(2020, Month::September)),
+            ),
+            (String::from("binary"), Err(Failure::NotUtf8)),
+            (
+                String::from("10-2020"),
+                Ok(test::month(2020, Month::October)),
+            ),
+            (
+                String::from("broken"),
+                Err(Failure::Unreadable(String::from("line 3"))),
+            ),
+        ];
+        let dataset = Dataset { station, files };
+        assert_eq!(decode(&encode(&dataset)), Some(dataset));
+    }
+
+    #[test]
+    fn empty() {
+        let empty = Dataset::default();
+        assert_eq!(decode(&encode(&empty)), Some(empty));
+    }
+
+    #[test]
+    fn cut_short() {
+        let dataset = Dataset {
+            files: vec![(
+                String::from("09-2020"),
+                Ok(test::month(2020, Month::September)),
+            )],
+            ..Dataset::default()
+        };
+        let bytes = encode(&dataset);
+        for len in [0, 1, bytes.len() / 2, bytes.len() - 1] {
+            assert_eq!(decode(&bytes[..len]), None, "{len} bytes");
+        }
+    }
+
+    #[test]
+    fn other_version() {
+        let mut bytes = encode(&Dataset::default());
+        bytes[0] = VERSION + 1;
+        assert_eq!(decode(&bytes), None);
+    }
+}
@@ -0,0 +1,159 @@
+use egui::{Color32, Context, Grid, RichText, Ui};
+
+use crate::{
+    i18n::{text, Language, Message, Text},
+    merge::Conflict,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Level {
+    Warning,
+    Error,
+}
+
+impl Level {
+    fn name(self) -> Text {
+        match self {
+            Level::Warning => text!("Avertissement"),
+            Level::Error => text!("Erreur"),
+        }
+    }
+
+    fn color(self) -> Color32 {
+        match self {
+            Level::Warning => Color32::from_rgb(220, 160, 40),
+            Level::Error => Color32::RED,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Diagnostic {
+    pub level: Level,
+    /// The file the problem comes from.
+    pub source: String,
+    pub message: Message,
+}
+
+/// Everything that went wrong while loading the reports. A broken report is skipped
+/// and ends up here instead of taking the whole app down.
+#[derive(Default, Debug, Clone)]
+pub struct Diagnostics {
+    entries: Vec<Diagnostic>,
+    /// How the days given by several reports were resolved.
+    conflicts: Vec<Conflict>,
+}
+
+impl Diagnostics {
+    pub fn warning(&mut self, source: impl Into<String>, message: Message) {
+        self.push(Level::Warning, source.into(), message);
+    }
+
+    pub fn error(&mut self, source: impl Into<String>, message: Message) {
+        self.push(Level::Error, source.into(), message);
+    }
+
+    fn push(&mut self, level: Level, source: String, message: Message) {
+        log::warn!("{source}: {message}");
+        self.entries.push(Diagnostic {
+            level,
+            source,
+            message,
+        });
+    }
+
+    pub fn conflicts(&mut self, conflicts: Vec<Conflict>) {
+        self.conflicts.extend(conflicts);
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn ui(&self, ctx: &Context) {
+        egui::CentralPanel::default().show(ctx, |ui| {
+            egui::ScrollArea::vertical().show(ui, |ui| {
+                self.entries_ui(ui);
+                if !self.conflicts.is_empty() {
+                    ui.add_space(12.0);
+                    self.conflicts_ui(ui);
+                }
+            });
+        });
+    }
+
+    fn entries_ui(&self, ui: &mut Ui) {
+        let lang = Language::get(ui.ctx());
+        if self.entries.is_empty() {
+            ui.label(lang.tr(text!("Tous les rapports ont été chargés sans problème.")));
+            return;
+        }
+        let errors = self
+            .entries
+            .iter()
+            .filter(|entry| entry.level == Level::Error)
+            .count();
+        ui.label(lang.trf(
+            text!("{} erreur(s) et {} avertissement(s) lors du chargement des rapports."),
+            &[&errors, &(self.entries.len() - errors)],
+        ));
+        ui.separator();
+
+        Grid::new("diagnostics")
+            .num_columns(3)
+            .striped(true)
+            .spacing([20.0, 6.0])
+            .show(ui, |ui| {
+                ui.label(RichText::new(lang.tr(text!("Niveau"))).strong());
+                ui.label(RichText::new(lang.tr(text!("Fichier"))).strong());
+                ui.label(RichText::new(lang.tr(text!("Problème"))).strong());
+                ui.end_row();
+
+                for entry in self.entries.iter() {
+                    let level = lang.tr(entry.level.name());
+                    ui.label(RichText::new(level).color(entry.level.color()));
+                    ui.label(&entry.source);
+                    ui.label(entry.message.tr(lang));
+                    ui.end_row();
+                }
+            });
+    }
+
+    fn conflicts_ui(&self, ui: &mut Ui) {
+        let lang = Language::get(ui.ctx());
+        ui.label(lang.trf(
+            text!("{} jour(s) donnés différemment par plusieurs rapports, les valeurs du premier rapport ont été gardées."),
+            &[&self.conflicts.len()],
+        ));
+        ui.separator();
+
+        Grid::new("conflicts")
+            .num_columns(4)
+            .striped(true)
+            .spacing([20.0, 6.0])
+            .show(ui, |ui| {
+                ui.label(RichText::new(lang.tr(text!("Jour"))).strong());
+                ui.label(RichText::new(lang.tr(text!("Gardé"))).strong());
+                ui.label(RichText::new(lang.tr(text!("Écarté"))).strong());
+                ui.label(RichText::new(lang.tr(text!("Mesures en désaccord"))).strong());
+                ui.end_row();
+
+                for conflict in self.conflicts.iter() {
+                    ui.label(lang.date(conflict.date));
+                    ui.label(conflict.kept.tr(lang));
+                    ui.label(conflict.dropped.tr(lang));
+                    let sensors: Vec<_> = conflict
+                        .sensors
+                        .iter()
+                        .map(|sensor| lang.tr(sensor.name()))
+                        .collect();
+                    ui.label(sensors.join(", "));
+                    ui.end_row();
+                }
+            });
+    }
+}
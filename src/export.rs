@@ -0,0 +1,164 @@
+use std::ops::RangeInclusive;
+
+use time::{macros::format_description, Date};
+
+use crate::{
+    i18n::{text, Language},
+    noaa,
+    units::{Quantity, Units},
+};
+
+/// The days of `report` within `range` as CSV, one row per day, in the units picked
+/// by the user. The units are in the header so the file can be read on its own, the
+/// numbers always have a dot and as many decimals as the app shows.
+pub fn to_csv(report: &noaa::Report, range: RangeInclusive<Date>, units: Units) -> String {
+    let date = format_description!("[year]-[month]-[day]");
+    let time = format_description!("[hour]:[minute]");
+
+    let mut days: Vec<_> = report
+        .days
+        .iter()
+        .filter(|day| range.contains(&day.date))
+        .collect();
+    days.sort_by_key(|day| day.date);
+
+    let temperature = units.symbol(Quantity::Temperature);
+    let rain = units.symbol(Quantity::Rain);
+    let wind = units.symbol(Quantity::Wind);
+    let mut csv = format!(
+        "date,temperature_moyenne ({temperature}),temperature_maximale ({temperature}),\
+         heure_maximale,temperature_minimale ({temperature}),heure_minimale,\
+         degres_jours_chauffage ({temperature}.j),degres_jours_climatisation ({temperature}.j),\
+         pluie ({rain}),vent_moyen ({wind}),rafale_maximale ({wind}),heure_rafale,\
+         direction_dominante\n",
+    );
+    let convert = |quantity: Quantity, value: f32| {
+        let decimals = units.decimals(quantity);
+        format!("{:.decimals$}", units.value(quantity, value as f64))
+    };
+    for day in days {
+        let gust_time = day
+            .high_wind_speed_date
+            .map(|at| at.format(time).unwrap())
+            .unwrap_or_default();
+        csv.push_str(&format!(
+            "{},{},{},{},{},{},{},{},{},{},{},{},{}\n",
+            day.date.format(date).unwrap(),
+            convert(Quantity::Temperature, day.mean_temp),
+            convert(Quantity::Temperature, day.high_temp),
+            day.high_temp_date.format(time).unwrap(),
+            convert(Quantity::Temperature, day.low_temp),
+            day.low_temp_date.format(time).unwrap(),
+            convert(Quantity::TemperatureDifference, day.heat_deg_days),
+            convert(Quantity::TemperatureDifference, day.cool_deg_days),
+            convert(Quantity::Rain, day.rain),
+            convert(Quantity::Wind, day.avg_wind_speed),
+            convert(Quantity::Wind, day.high_wind_speed),
+            gust_time,
+            day.dom_dir.trim(),
+        ));
+    }
+    csv
+}
+
+/// Asks the user where to save the file and returns where it went.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn save(name: &str, content: String, lang: Language) -> Result<String, String> {
+    let Some(path) = rfd::FileDialog::new()
+        .set_title(lang.tr(text!("Exporter")))
+        .set_file_name(name)
+        .add_filter("CSV", &["csv"])
+        .save_file()
+    else {
+        return Ok(lang.tr(text!("Export annulé")).to_string());
+    };
+    std::fs::write(&path, content).map_err(|e| e.to_string())?;
+    Ok(lang.trf(text!("Exporté dans {}"), &[&path.display()]))
+}
+
+/// Makes the browser download the file.
+#[cfg(target_arch = "wasm32")]
+pub fn save(name: &str, content: String, lang: Language) -> Result<String, String> {
+    use eframe::wasm_bindgen::{closure::Closure, JsCast, JsValue};
+    use web_sys::{Blob, BlobPropertyBag, HtmlAnchorElement, Url};
+
+    let window = web_sys::window().ok_or("Pas de fenêtre")?;
+    let link = window
+        .document()
+        .ok_or("Pas de document")?
+        .create_element("a")
+        .map_err(|e| format!("{e:?}"))?
+        .dyn_into::<HtmlAnchorElement>()
+        .map_err(|e| format!("{e:?}"))?;
+    let options = BlobPropertyBag::new();
+    options.set_type("text/csv;charset=utf-8");
+    let blob = Blob::new_with_str_sequence_and_options(
+        &js_sys::Array::of1(&JsValue::from_str(&content)),
+        &options,
+    )
+    .map_err(|e| format!("{e:?}"))?;
+    let url = Url::create_object_url_with_blob(&blob).map_err(|e| format!("{e:?}"))?;
+    link.set_href(&url);
+    link.set_download(name);
+    link.click();
+    // the download may only start once the click is handled, the URL holds the blob
+    // in memory until it's revoked
+    let revoke = Closure::once_into_js(move || {
+        let _ = Url::revoke_object_url(&url);
+    });
+    window
+        .set_timeout_with_callback_and_timeout_and_arguments_0(revoke.unchecked_ref(), 60_000)
+        .map_err(|e| format!("{e:?}"))?;
+    Ok(lang.trf(text!("{} téléchargé"), &[&name]))
+}
+
+#[cfg(test)]
+mod tests {
+    use time::{macros::date, Month};
+
+    use super::*;
+    use crate::units::{Rain, Temperature, Wind};
+
+    #[test]
+    fn metric() {
+        let report = noaa::test::month(2020, Month::September);
+        let csv = to_csv(
+            &report,
+            date!(2020 - 09 - 01)..=date!(2020 - 09 - 01),
+            Units::default(),
+        );
+        let lines: Vec<_> = csv.lines().collect();
+        assert_eq!(lines.len(), 2);
+        assert!(lines[0].contains("temperature_moyenne (°C)"));
+        assert!(lines[0].contains("pluie (mm)"));
+        assert!(lines[0].contains("vent_moyen (km/h)"));
+        assert_eq!(
+            lines[1],
+            "2020-09-01,10.0,15.5,14:30,4.5,05:30,8.3,0.0,0.0,3.2,25.7,16:30,NNE"
+        );
+    }
+
+    #[test]
+    fn imperial() {
+        let report = noaa::test::month(2020, Month::September);
+        let units = Units {
+            temperature: Temperature::Fahrenheit,
+            rain: Rain::Inch,
+            wind: Wind::Knot,
+        };
+        let csv = to_csv(
+            &report,
+            date!(2020 - 09 - 04)..=date!(2020 - 09 - 05),
+            units,
+        );
+        let lines: Vec<_> = csv.lines().collect();
+        assert_eq!(lines.len(), 3);
+        assert!(lines[0].contains("temperature_moyenne (°F)"));
+        assert!(lines[0].contains("pluie (in)"));
+        assert!(lines[0].contains("vent_moyen (kn)"));
+        assert_eq!(
+            lines[1],
+            "2020-09-04,55.4,65.3,14:30,45.5,05:30,14.9,0.0,0.06,3.3,15.5,,NNE"
+        );
+    }
+}
@@ -0,0 +1,92 @@
+use std::sync::{Arc, Mutex};
+
+use crate::{
+    dataset::{self, Dataset},
+    i18n::{text, Message},
+};
+
+/// The directory holding the texts of the reports next to the dataset.
+const REPORTS: &str = "raw";
+
+/// Downloads the dataset of a station from the server hosting the app, so deploying
+/// new reports doesn't require to rebuild the app.
+#[derive(Clone)]
+pub struct FetchReports {
+    result: Arc<Mutex<Option<Result<Dataset, Message>>>>,
+}
+
+impl FetchReports {
+    /// Downloads the dataset `prepare-data` wrote in `remote`, a single request
+    /// already parsed.
+    pub fn start(ctx: egui::Context, remote: &str) -> Self {
+        let fetch = Self {
+            result: Arc::default(),
+        };
+        let result = fetch.result.clone();
+        let url = format!("{remote}/{}", dataset::FILE_NAME);
+        ehttp::fetch(ehttp::Request::get(&url), move |response| {
+            let dataset = response.map_err(Message::from).and_then(|response| {
+                if !response.ok {
+                    return Err(status(&response));
+                }
+                dataset::decode(&response.bytes).ok_or_else(|| {
+                    Message::new(text!("{} n'est pas un jeu de données valide"), &[&url])
+                })
+            });
+            *result.lock().unwrap() = Some(dataset);
+            ctx.request_repaint();
+        });
+        fetch
+    }
+
+    /// The dataset once it's downloaded, or why it couldn't be.
+    pub fn poll(&self) -> Option<Result<Dataset, Message>> {
+        self.result.lock().unwrap().take()
+    }
+}
+
+/// Where the text of the report `name` is served, relative to the app.
+pub fn report_url(remote: &str, name: &str) -> String {
+    format!("{remote}/{REPORTS}/{name}")
+}
+
+/// The original text of a report for the Text tab, downloaded the first time it's
+/// asked for. `None` while it's downloading.
+pub fn original(ctx: &egui::Context, url: &str) -> Option<Result<String, Message>> {
+    type Slot = Arc<Mutex<Option<Result<String, Message>>>>;
+    let id = egui::Id::new(("original", url));
+    let slot = match ctx.data(|data| data.get_temp::<Slot>(id)) {
+        Some(slot) => slot,
+        None => {
+            let slot = Slot::default();
+            ctx.data_mut(|data| data.insert_temp(id, slot.clone()));
+            let (ctx, downloaded) = (ctx.clone(), slot.clone());
+            ehttp::fetch(ehttp::Request::get(url), move |response| {
+                *downloaded.lock().unwrap() = Some(text(response));
+                ctx.request_repaint();
+            });
+            slot
+        }
+    };
+    let original = slot.lock().unwrap().clone();
+    original
+}
+
+fn text(response: ehttp::Result<ehttp::Response>) -> Result<String, Message> {
+    let response = response?;
+    if !response.ok {
+        return Err(status(&response));
+    }
+    response
+        .text()
+        .map(String::from)
+        .ok_or_else(|| Message::new(text!("{} n'est pas du texte"), &[&response.url]))
+}
+
+/// Why the server refused a request.
+fn status(response: &ehttp::Response) -> Message {
+    Message::from(format!(
+        "{} {} ({})",
+        response.status, response.status_text, response.url
+    ))
+}
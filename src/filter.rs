@@ -0,0 +1,429 @@
+use std::ops::RangeInclusive;
+
+use egui::{DragValue, TextEdit, Ui};
+use time::{Date, Month};
+
+use crate::{
+    i18n::{text, Language, Text},
+    noaa,
+    report::Report,
+    units::{Quantity, Units},
+};
+
+/// The meteorological seasons, each one is three whole months.
+#[derive(Debug, PartialEq, Clone, Copy)]
+enum Season {
+    Winter,
+    Spring,
+    Summer,
+    Autumn,
+}
+
+impl Season {
+    const ALL: [Season; 4] = [
+        Season::Winter,
+        Season::Spring,
+        Season::Summer,
+        Season::Autumn,
+    ];
+
+    fn name(self) -> Text {
+        match self {
+            Season::Winter => text!("Hiver"),
+            Season::Spring => text!("Printemps"),
+            Season::Summer => text!("Été"),
+            Season::Autumn => text!("Automne"),
+        }
+    }
+
+    fn months(self) -> [Month; 3] {
+        match self {
+            Season::Winter => [Month::December, Month::January, Month::February],
+            Season::Spring => [Month::March, Month::April, Month::May],
+            Season::Summer => [Month::June, Month::July, Month::August],
+            Season::Autumn => [Month::September, Month::October, Month::November],
+        }
+    }
+}
+
+/// What a condition looks at in the days of a report.
+#[derive(Debug, PartialEq, Clone, Copy)]
+enum Measure {
+    TotalRain,
+    HighestRain,
+    LowestTemp,
+    HighestTemp,
+    MeanTemp,
+    HighestGust,
+    MeanWind,
+}
+
+impl Measure {
+    /// How the measures are written in a condition, in every language so a
+    /// filter keeps working when the language changes.
+    const NAMES: [(&'static str, Measure); 18] = [
+        ("pluie totale", Measure::TotalRain),
+        ("pluie", Measure::TotalRain),
+        ("total rain", Measure::TotalRain),
+        ("rain", Measure::TotalRain),
+        ("pluie max", Measure::HighestRain),
+        ("max rain", Measure::HighestRain),
+        ("min", Measure::LowestTemp),
+        ("max", Measure::HighestTemp),
+        ("moyenne", Measure::MeanTemp),
+        ("mean", Measure::MeanTemp),
+        ("rafale", Measure::HighestGust),
+        ("rafales", Measure::HighestGust),
+        ("gust", Measure::HighestGust),
+        ("gusts", Measure::HighestGust),
+        ("vent", Measure::MeanWind),
+        ("vent moyen", Measure::MeanWind),
+        ("wind", Measure::MeanWind),
+        ("mean wind", Measure::MeanWind),
+    ];
+
+    fn parse(name: &str) -> Option<Self> {
+        let name = name.split_whitespace().collect::<Vec<_>>().join(" ");
+        Self::NAMES
+            .iter()
+            .find(|(known, _)| name.eq_ignore_ascii_case(known))
+            .map(|(_, measure)| *measure)
+    }
+
+    fn quantity(self) -> Quantity {
+        match self {
+            Measure::TotalRain | Measure::HighestRain => Quantity::Rain,
+            Measure::LowestTemp | Measure::HighestTemp | Measure::MeanTemp => Quantity::Temperature,
+            Measure::HighestGust | Measure::MeanWind => Quantity::Wind,
+        }
+    }
+
+    /// The measure over the whole report, `None` if it has no days.
+    fn value(self, report: &noaa::Report) -> Option<f64> {
+        if report.days.is_empty() {
+            return None;
+        }
+        let values = report.days.iter().map(|day| match self {
+            Measure::TotalRain | Measure::HighestRain => day.rain as f64,
+            Measure::LowestTemp => day.low_temp as f64,
+            Measure::HighestTemp => day.high_temp as f64,
+            Measure::MeanTemp => day.mean_temp as f64,
+            Measure::HighestGust => day.high_wind_speed as f64,
+            Measure::MeanWind => day.avg_wind_speed as f64,
+        });
+        Some(match self {
+            Measure::TotalRain => values.sum(),
+            Measure::LowestTemp => values.fold(f64::INFINITY, f64::min),
+            Measure::HighestRain | Measure::HighestTemp | Measure::HighestGust => {
+                values.fold(f64::NEG_INFINITY, f64::max)
+            }
+            Measure::MeanTemp | Measure::MeanWind => values.sum::<f64>() / report.days.len() as f64,
+        })
+    }
+}
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+enum Operator {
+    Less,
+    LessOrEqual,
+    Equal,
+    GreaterOrEqual,
+    Greater,
+}
+
+impl Operator {
+    /// The two characters operators come first so `<=` isn't read as `<`.
+    const SYMBOLS: [(&'static str, Operator); 7] = [
+        ("<=", Operator::LessOrEqual),
+        (">=", Operator::GreaterOrEqual),
+        ("≤", Operator::LessOrEqual),
+        ("≥", Operator::GreaterOrEqual),
+        ("<", Operator::Less),
+        (">", Operator::Greater),
+        ("=", Operator::Equal),
+    ];
+
+    fn holds(self, value: f64, threshold: f64) -> bool {
+        match self {
+            Operator::Less => value < threshold,
+            Operator::LessOrEqual => value <= threshold,
+            // the reports only have one decimal
+            Operator::Equal => (value - threshold).abs() < 0.05,
+            Operator::GreaterOrEqual => value >= threshold,
+            Operator::Greater => value > threshold,
+        }
+    }
+}
+
+/// A condition on the days of a report, like `pluie totale > 100`. The threshold is
+/// in the units picked by the user.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct Condition {
+    measure: Measure,
+    operator: Operator,
+    threshold: f64,
+}
+
+impl Condition {
+    fn parse(text: &str, lang: Language) -> Result<Self, String> {
+        let (position, symbol, operator) = Operator::SYMBOLS
+            .iter()
+            .find_map(|(symbol, operator)| Some((text.find(symbol)?, *symbol, *operator)))
+            .ok_or_else(|| lang.trf(text!("Il manque une comparaison dans « {} »"), &[&text]))?;
+        let name = &text[..position];
+        let threshold = text[position + symbol.len()..].trim();
+        let measure = Measure::parse(name)
+            .ok_or_else(|| lang.trf(text!("Mesure inconnue : « {} »"), &[&name.trim()]))?;
+        // both decimal separators are accepted whatever the language
+        let threshold = threshold
+            .replace(',', ".")
+            .parse()
+            .map_err(|_| lang.trf(text!("Valeur illisible : « {} »"), &[&threshold]))?;
+        Ok(Self {
+            measure,
+            operator,
+            threshold,
+        })
+    }
+
+    fn matches(&self, report: &noaa::Report, units: Units) -> bool {
+        self.measure.value(report).is_some_and(|value| {
+            let value = units.value(self.measure.quantity(), value);
+            self.operator.holds(value, self.threshold)
+        })
+    }
+}
+
+/// Narrows the list of reports down by period and by what happened in them.
+#[derive(Default, Debug, PartialEq, Clone, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+pub struct ReportFilter {
+    /// `None` keeps every year.
+    years: Option<RangeInclusive<i32>>,
+    /// The months kept, from January to December. None selected keeps them all.
+    months: [bool; 12],
+    /// The conditions as typed by the user, separated by `;`.
+    conditions: String,
+}
+
+impl ReportFilter {
+    /// Whether the filter may hide some reports.
+    pub fn is_active(&self) -> bool {
+        self.years.is_some() || self.months.contains(&true) || !self.conditions.trim().is_empty()
+    }
+
+    /// The conditions typed by the user, or why they can't be read.
+    pub fn conditions(&self, lang: Language) -> Result<Vec<Condition>, String> {
+        self.conditions
+            .split(';')
+            .filter(|condition| !condition.trim().is_empty())
+            .map(|condition| Condition::parse(condition, lang))
+            .collect()
+    }
+
+    /// The year `date` counts for. With the whole winter selected, December belongs
+    /// to the winter of the next year: 2021 keeps December 2020 to February 2021.
+    fn year(&self, date: Date) -> i32 {
+        let winter = Season::Winter
+            .months()
+            .iter()
+            .all(|month| self.months[*month as usize - 1]);
+        if winter && date.month() == Month::December {
+            date.year() + 1
+        } else {
+            date.year()
+        }
+    }
+
+    /// Whether `report` is kept, `conditions` come from [`ReportFilter::conditions`].
+    pub fn matches(&self, report: &Report, conditions: &[Condition], units: Units) -> bool {
+        let date = report.report.metadata.date;
+        let in_years = self
+            .years
+            .as_ref()
+            .is_none_or(|years| years.contains(&self.year(date)));
+        let in_months = !self.months.contains(&true) || self.months[date.month() as usize - 1];
+        in_years
+            && in_months
+            && conditions
+                .iter()
+                .all(|condition| condition.matches(&report.report, units))
+    }
+
+    /// `available` are the years of the reports, the default range of years.
+    pub fn ui(&mut self, available: RangeInclusive<i32>, ui: &mut Ui) {
+        let lang = Language::get(ui.ctx());
+
+        ui.horizontal(|ui| {
+            let mut by_year = self.years.is_some();
+            ui.checkbox(&mut by_year, lang.tr(text!("Années :")));
+            match (by_year, &mut self.years) {
+                (true, None) => self.years = Some(available.clone()),
+                (false, Some(_)) => self.years = None,
+                _ => {}
+            }
+            if let Some(years) = &mut self.years {
+                let (mut start, mut end) = years.clone().into_inner();
+                ui.add(DragValue::new(&mut start).range(*available.start()..=end));
+                ui.label("-");
+                ui.add(DragValue::new(&mut end).range(start..=*available.end()));
+                *years = start..=end;
+            }
+        });
+
+        ui.horizontal_wrapped(|ui| {
+            for season in Season::ALL {
+                let months = season.months().map(|month| month as usize - 1);
+                let mut selected = months.iter().all(|month| self.months[*month]);
+                let mut toggle = ui.toggle_value(&mut selected, lang.tr(season.name()));
+                if season == Season::Winter {
+                    toggle = toggle.on_hover_text(
+                        lang.tr(text!("Décembre compte pour l'hiver de l'année suivante.")),
+                    );
+                }
+                if toggle.clicked() {
+                    for month in months {
+                        self.months[month] = selected;
+                    }
+                }
+            }
+        });
+        ui.horizontal_wrapped(|ui| {
+            let mut month = Month::January;
+            for selected in self.months.iter_mut() {
+                ui.toggle_value(selected, lang.month(month));
+                month = month.next();
+            }
+        });
+
+        ui.add(
+            TextEdit::singleline(&mut self.conditions)
+                .hint_text(lang.tr(text!("pluie totale > 100; min < -5"))),
+        )
+        .on_hover_text(lang.tr(text!(
+            "Mesures : pluie totale, pluie max, min, max, moyenne, rafale, vent. \
+             Les valeurs sont dans les unités choisies."
+        )));
+        if let Err(error) = self.conditions(lang) {
+            ui.colored_label(ui.visuals().error_fg_color, error);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use time::macros::date;
+
+    use super::*;
+
+    fn filter(months: &[Month]) -> ReportFilter {
+        let mut filter = ReportFilter::default();
+        for month in months {
+            filter.months[*month as usize - 1] = true;
+        }
+        filter
+    }
+
+    fn parse(text: &str) -> Result<Condition, String> {
+        Condition::parse(text, Language::French)
+    }
+
+    fn condition(measure: Measure, operator: Operator, threshold: f64) -> Condition {
+        Condition {
+            measure,
+            operator,
+            threshold,
+        }
+    }
+
+    #[test]
+    fn parse_conditions() {
+        assert_eq!(
+            parse("pluie totale > 100"),
+            Ok(condition(Measure::TotalRain, Operator::Greater, 100.0))
+        );
+        assert_eq!(
+            parse("min < -5"),
+            Ok(condition(Measure::LowestTemp, Operator::Less, -5.0))
+        );
+        assert_eq!(
+            parse("rafale ≥ 80"),
+            Ok(condition(
+                Measure::HighestGust,
+                Operator::GreaterOrEqual,
+                80.0
+            ))
+        );
+        assert_eq!(
+            parse("max<=3,5"),
+            Ok(condition(Measure::HighestTemp, Operator::LessOrEqual, 3.5))
+        );
+        // the names are the same in every language
+        assert_eq!(
+            parse("  Total   Rain = 12.5 "),
+            Ok(condition(Measure::TotalRain, Operator::Equal, 12.5))
+        );
+    }
+
+    #[test]
+    fn parse_errors() {
+        assert_eq!(
+            parse("pluie 100"),
+            Err(String::from("Il manque une comparaison dans « pluie 100 »"))
+        );
+        assert_eq!(
+            parse("neige > 3"),
+            Err(String::from("Mesure inconnue : « neige »"))
+        );
+        assert_eq!(
+            parse("pluie > beaucoup"),
+            Err(String::from("Valeur illisible : « beaucoup »"))
+        );
+        assert_eq!(
+            Condition::parse("neige > 3", Language::English),
+            Err(String::from("Unknown measure: “neige”"))
+        );
+    }
+
+    #[test]
+    fn conditions_are_separated_by_semicolons() {
+        let filter = ReportFilter {
+            conditions: String::from("pluie totale > 100; min < -5;"),
+            ..ReportFilter::default()
+        };
+        assert_eq!(
+            filter.conditions(Language::French),
+            Ok(vec![
+                condition(Measure::TotalRain, Operator::Greater, 100.0),
+                condition(Measure::LowestTemp, Operator::Less, -5.0),
+            ])
+        );
+
+        let broken = ReportFilter {
+            conditions: String::from("pluie totale > 100; neige"),
+            ..ReportFilter::default()
+        };
+        assert!(broken.conditions(Language::French).is_err());
+    }
+
+    #[test]
+    fn december_counts_for_the_next_winter() {
+        let winter = filter(&Season::Winter.months());
+        assert_eq!(winter.year(date!(2020 - 12 - 01)), 2021);
+        assert_eq!(winter.year(date!(2021 - 01 - 01)), 2021);
+        assert_eq!(winter.year(date!(2021 - 02 - 01)), 2021);
+    }
+
+    #[test]
+    fn december_alone_keeps_its_year() {
+        assert_eq!(filter(&[]).year(date!(2020 - 12 - 01)), 2020);
+        assert_eq!(filter(&[Month::December]).year(date!(2020 - 12 - 01)), 2020);
+        let autumn_and_december = filter(&[
+            Month::September,
+            Month::October,
+            Month::November,
+            Month::December,
+        ]);
+        assert_eq!(autumn_and_december.year(date!(2020 - 12 - 01)), 2020);
+    }
+}
@@ -0,0 +1,462 @@
+use std::fmt::Display;
+
+use egui::{Context, Id, Ui};
+use time::{
+    format_description::BorrowedFormatItem, macros::format_description, Date, Month,
+    PrimitiveDateTime,
+};
+
+/// The language of the interface.
+///
+/// The texts are written in French in the code with [`text!`] and looked up in the
+/// [`CATALOG`]. Like the [`crate::units::Units`] it's stored in the [`Context`]
+/// every frame.
+#[derive(Default, Debug, PartialEq, Eq, Clone, Copy, serde::Serialize, serde::Deserialize)]
+pub enum Language {
+    #[default]
+    French,
+    English,
+}
+
+impl Language {
+    fn id() -> Id {
+        Id::new("language")
+    }
+
+    /// The language picked by the user.
+    pub fn get(ctx: &Context) -> Self {
+        ctx.data(|data| data.get_temp(Self::id()))
+            .unwrap_or_default()
+    }
+
+    pub fn store(self, ctx: &Context) {
+        ctx.data_mut(|data| data.insert_temp(Self::id(), self));
+    }
+
+    /// Translates a text of the interface.
+    pub fn tr(self, text: Text) -> &'static str {
+        let (french, english) = CATALOG[text.0];
+        match self {
+            Language::French => french,
+            Language::English => english,
+        }
+    }
+
+    /// Translates a text with `{}` placeholders, they're replaced by `args` in order.
+    pub fn trf(self, text: Text, args: &[&dyn Display]) -> String {
+        let mut args = args.iter();
+        let mut parts = self.tr(text).split("{}");
+        let mut text = String::from(parts.next().unwrap_or_default());
+        for part in parts {
+            if let Some(arg) = args.next() {
+                text.push_str(&arg.to_string());
+            }
+            text.push_str(part);
+        }
+        text
+    }
+
+    /// A number with a decimal comma in French.
+    pub fn number(self, value: f64, decimals: usize) -> String {
+        let number = format!("{value:.decimals$}");
+        match self {
+            Language::French => number.replace('.', ","),
+            Language::English => number,
+        }
+    }
+
+    /// How the dates are written, and read in the date fields.
+    pub fn date_format(self) -> &'static [BorrowedFormatItem<'static>] {
+        match self {
+            Language::French => format_description!("[day]/[month]/[year]"),
+            Language::English => format_description!("[year]-[month]-[day]"),
+        }
+    }
+
+    pub fn date(self, date: Date) -> String {
+        date.format(self.date_format()).unwrap()
+    }
+
+    pub fn date_time(self, at: PrimitiveDateTime) -> String {
+        let time = at.format(format_description!("[hour]:[minute]")).unwrap();
+        self.trf(text!("{} à {}"), &[&self.date(at.date()), &time])
+    }
+
+    pub fn month(self, month: Month) -> &'static str {
+        match self {
+            Language::French => match month {
+                Month::January => "Janvier",
+                Month::February => "Février",
+                Month::March => "Mars",
+                Month::April => "Avril",
+                Month::May => "Mai",
+                Month::June => "Juin",
+                Month::July => "Juillet",
+                Month::August => "Aout",
+                Month::September => "Septembre",
+                Month::October => "Octobre",
+                Month::November => "Novembre",
+                Month::December => "Décembre",
+            },
+            Language::English => match month {
+                Month::January => "January",
+                Month::February => "February",
+                Month::March => "March",
+                Month::April => "April",
+                Month::May => "May",
+                Month::June => "June",
+                Month::July => "July",
+                Month::August => "August",
+                Month::September => "September",
+                Month::October => "October",
+                Month::November => "November",
+                Month::December => "December",
+            },
+        }
+    }
+
+    /// Lets the user pick the language, next to the theme buttons.
+    pub fn ui(&mut self, ui: &mut Ui) {
+        ui.selectable_value(self, Language::English, "EN")
+            .on_hover_text("English");
+        ui.selectable_value(self, Language::French, "FR")
+            .on_hover_text("Français");
+    }
+}
+
+/// A text made before the language is known, like the diagnostics of the reports
+/// loaded at startup, it's translated when it's shown.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Message {
+    /// A text of the catalog and the arguments of its `{}`, see [`Language::trf`].
+    Text(Text, Vec<String>),
+    /// Shown as is, like a file name or what a library said.
+    Verbatim(String),
+}
+
+impl Message {
+    pub fn new(text: Text, args: &[&dyn Display]) -> Self {
+        Message::Text(text, args.iter().map(ToString::to_string).collect())
+    }
+
+    pub fn tr(&self, lang: Language) -> String {
+        match self {
+            Message::Text(text, args) => {
+                let args: Vec<&dyn Display> = args.iter().map(|arg| arg as &dyn Display).collect();
+                lang.trf(*text, &args)
+            }
+            Message::Verbatim(text) => text.clone(),
+        }
+    }
+}
+
+impl From<String> for Message {
+    fn from(text: String) -> Self {
+        Message::Verbatim(text)
+    }
+}
+
+/// In French, for the logs.
+impl Display for Message {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.tr(Language::French))
+    }
+}
+
+/// A text of the interface, checked against the [`CATALOG`] when the app is built.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Text(usize);
+
+impl Text {
+    /// Looks a text up by its French version, use [`text!`] so a text missing from
+    /// the catalog doesn't build.
+    pub const fn new(french: &str) -> Self {
+        let mut index = 0;
+        while index < CATALOG.len() {
+            if same(CATALOG[index].0, french) {
+                return Text(index);
+            }
+            index += 1;
+        }
+        panic!("a text is missing from the catalog of `src/i18n.rs`");
+    }
+}
+
+/// `str` can't be compared in a `const fn` yet.
+const fn same(a: &str, b: &str) -> bool {
+    let (a, b) = (a.as_bytes(), b.as_bytes());
+    if a.len() != b.len() {
+        return false;
+    }
+    let mut index = 0;
+    while index < a.len() {
+        if a[index] != b[index] {
+            return false;
+        }
+        index += 1;
+    }
+    true
+}
+
+/// A text of the interface by its French version, the build fails when it's missing
+/// from the [`CATALOG`].
+macro_rules! text {
+    ($french:literal) => {{
+        const TEXT: $crate::i18n::Text = $crate::i18n::Text::new($french);
+        TEXT
+    }};
+}
+pub(crate) use text;
+
+/// Every text of the interface, in French then in English.
+const CATALOG: &[(&str, &str)] = &[
+    ("{} à {}", "{} at {}"),
+    // shared
+    ("Aucune donnée à afficher", "No data to show"),
+    ("Température moyenne", "Mean temperature"),
+    ("Température maximale", "High temperature"),
+    ("Température minimale", "Low temperature"),
+    ("Températures", "Temperatures"),
+    ("Pluie", "Rain"),
+    ("Rafales", "Gusts"),
+    ("Vent", "Wind"),
+    ("Degrés-jours", "Degree days"),
+    ("Rafale maximale", "Highest gust"),
+    ("Direction dominante", "Dominant direction"),
+    ("Période", "Period"),
+    ("Problème", "Issue"),
+    ("Jour", "Day"),
+    ("Jour de l'année", "Day of the year"),
+    ("Jour du mois", "Day of the month"),
+    ("Écart à la normale", "Departure from normal"),
+    ("{} en {}", "{} in {}"),
+    ("le {}", "on {}"),
+    ("{} le {}", "{} on {}"),
+    ("{} jours", "{} days"),
+    // app
+    ("Vue globale", "Overview"),
+    ("Inspecter les rapports individuel", "Inspect individual reports"),
+    ("Calendrier", "Calendar"),
+    ("Records", "Records"),
+    ("Comparer les stations", "Compare stations"),
+    ("À propos", "About"),
+    ("Diagnostic", "Diagnostics"),
+    ("⚠ Diagnostic ({})", "⚠ Diagnostics ({})"),
+    ("Aucun rapport à afficher", "No report to show"),
+    ("Les rapports météo n'ont pas pu être chargés, réessayez plus tard.", "The weather reports could not be loaded, please try again later."),
+    ("Le diagnostic détaille ce qui n'a pas fonctionné.", "The diagnostics explain what went wrong."),
+    ("Chargement des rapports…", "Loading the reports…"),
+    ("⚠ Rapports hors ligne", "⚠ Offline reports"),
+    ("Les rapports n'ont pas pu être téléchargés : {}", "The reports could not be downloaded: {}"),
+    ("Salut, je m'appelle", "Hi, my name is"),
+    (", ou juste", ", or just"),
+    ("sur internet. J'ai", "on the internet. I'm"),
+    ("C'est jeune", "That's young"),
+    ("ans et je suis développeur pour", "years old and I'm a developer for"),
+    ("en télétravail. J'habite", "working remotely. I've been living"),
+    ("au Vigan", "in Le Vigan"),
+    ("depuis {} mois", "for {} months"),
+    ("depuis {} an et {} mois", "for {} year and {} months"),
+    ("depuis {} ans", "for {} years"),
+    ("et j'ai fait ce site après avoir découvert que le lycée à côté de chez moi collecte des données météorologiques depuis 2006.", "and I made this website after finding out that the high school next to my home has been collecting weather data since 2006."),
+    ("Toutes les données affichées sur mon site viennent en réalité de :", "All the data shown on my website actually comes from:"),
+    ("Elles sont mises à jour tous les jours à 2h du matin.", "It's updated every day at 2 a.m."),
+    ("L'intégralité du code qui génère ce site web est disponible", "All the code behind this website is available"),
+    ("ici", "here"),
+    ("où vous pouvez m'y faire des suggestions via les « issues ».", "where you can send me suggestions through the issues."),
+    // comparison
+    ("Années :", "Years:"),
+    ("Aucune", "None"),
+    // diagnostics
+    ("Tous les rapports ont été chargés sans problème.", "Every report was loaded without any issue."),
+    ("{} erreur(s) et {} avertissement(s) lors du chargement des rapports.", "{} error(s) and {} warning(s) while loading the reports."),
+    ("Niveau", "Level"),
+    ("Fichier", "File"),
+    ("{} jour(s) donnés différemment par plusieurs rapports, les valeurs du premier rapport ont été gardées.", "{} day(s) given differently by several reports, the values of the first report were kept."),
+    ("Gardé", "Kept"),
+    ("Écarté", "Discarded"),
+    ("Mesures en désaccord", "Conflicting measures"),
+    ("Avertissement", "Warning"),
+    ("Erreur", "Error"),
+    ("Le rapport ne contient aucun jour", "The report has no day"),
+    ("Le fichier n'est pas en UTF-8", "The file isn't UTF-8"),
+    ("Rapport illisible : {}", "Unreadable report: {}"),
+    ("{} n'est pas un jeu de données valide", "{} isn't a valid dataset"),
+    ("{} n'est pas du texte", "{} isn't text"),
+    // export
+    ("Exporté dans {}", "Exported to {}"),
+    ("Export annulé", "Export cancelled"),
+    ("{} téléchargé", "{} downloaded"),
+    ("Exporter", "Export"),
+    ("Échec de l'export : {}", "Export failed: {}"),
+    ("Période visible uniquement", "Visible period only"),
+    // inspect
+    ("Hiver", "Winter"),
+    ("Décembre compte pour l'hiver de l'année suivante.", "December counts for the winter of the next year."),
+    ("Printemps", "Spring"),
+    ("Été", "Summer"),
+    ("Automne", "Autumn"),
+    ("Il manque une comparaison dans « {} »", "A comparison is missing in “{}”"),
+    ("Mesure inconnue : « {} »", "Unknown measure: “{}”"),
+    ("Valeur illisible : « {} »", "Unreadable value: “{}”"),
+    ("pluie totale > 100; min < -5", "total rain > 100; min < -5"),
+    ("Mesures : pluie totale, pluie max, min, max, moyenne, rafale, vent. \
+     Les valeurs sont dans les unités choisies.", "Measures: total rain, max rain, min, max, mean, gust, wind. \
+     The values are in the chosen units."),
+    ("Total : {}", "Total: {}"),
+    ("Après filtre : {}", "After filter: {}"),
+    ("Tout sélectionner", "Select all"),
+    ("Tout fermer", "Close all"),
+    // normals
+    ("Période de référence :", "Reference period:"),
+    // plot
+    ("Date", "Date"),
+    // quality
+    ("Jours manquants", "Missing days"),
+    ("{} bloquée à {}", "{} stuck at {}"),
+    ("{} impossible : {}", "{} out of range: {}"),
+    ("Températures incohérentes entre minimum, moyenne et maximum", "Inconsistent low, mean and high temperatures"),
+    ("Jour identique au précédent", "Same day as the previous one"),
+    ("du {} au {}", "from {} to {}"),
+    ("température moyenne", "mean temperature"),
+    ("température maximale", "high temperature"),
+    ("température minimale", "low temperature"),
+    ("pluie", "rain"),
+    ("vent moyen", "mean wind"),
+    ("rafales", "gusts"),
+    // range
+    ("Du", "From"),
+    ("au", "to"),
+    ("7 jours", "7 days"),
+    ("30 jours", "30 days"),
+    ("1 an", "1 year"),
+    ("tout", "all"),
+    // records
+    ("Tous les mois", "Every month"),
+    ("Record", "Record"),
+    ("Valeur", "Value"),
+    ("Établi le", "Set on"),
+    ("Record précédent", "Previous record"),
+    ("Inspecter le rapport", "Inspect the report"),
+    ("{} le", "{} on"),
+    ("Température la plus haute", "Highest temperature"),
+    ("Température la plus basse", "Lowest temperature"),
+    ("Journée la plus chaude en moyenne", "Warmest day on average"),
+    ("Journée la plus froide en moyenne", "Coldest day on average"),
+    ("Journée la plus pluvieuse", "Wettest day"),
+    ("Rafale la plus forte", "Strongest gust"),
+    ("Nuit la plus chaude", "Warmest night"),
+    ("Journée la plus froide", "Coldest day"),
+    // report
+    ("données suspectes", "suspicious data"),
+    ("{} chauffage, {} climatisation", "{} heating, {} cooling"),
+    ("Vent moyen", "Mean wind"),
+    ("Rose des vents", "Wind rose"),
+    ("Comparaison", "Comparison"),
+    ("Résumé", "Summary"),
+    ("Qualité", "Quality"),
+    ("Texte", "Text"),
+    ("Aucune donnée suspecte dans ce rapport.", "No suspicious data in this report."),
+    ("{} problème(s) détecté(s)", "{} issue(s) found"),
+    ("Voir sur les courbes", "Show on the plots"),
+    ("données manquantes", "missing data"),
+    ("Quotidienne", "Daily"),
+    ("Normales", "Normals"),
+    ("Température en {}", "Temperature in {}"),
+    ("normale minimale", "normal low"),
+    ("normale moyenne", "normal mean"),
+    ("normale maximale", "normal high"),
+    ("temperature minimale", "low temperature"),
+    ("temperature moyenne", "mean temperature"),
+    ("temperature maximale", "high temperature"),
+    ("écart minimum–maximum", "minimum–maximum range"),
+    ("Cumul annuel", "Year to date"),
+    ("Pluie en {}", "Rain in {}"),
+    ("Écart à la normale en {}", "Departure from normal in {}"),
+    ("plus chaud que la normale", "warmer than normal"),
+    ("plus froid que la normale", "colder than normal"),
+    ("Pluie en % de la normale", "Rain in % of normal"),
+    ("{} : {} pour une normale de {}", "{}: {} for a normal of {}"),
+    ("{} ({} jours sur {})", "{} ({} days out of {})"),
+    ("normale", "normal"),
+    ("plus humide que la normale", "wetter than normal"),
+    ("plus sec que la normale", "drier than normal"),
+    ("Pluie depuis le 1er janvier en {}", "Rain since January 1st in {}"),
+    ("minimum historique", "historical minimum"),
+    ("moyenne historique", "historical mean"),
+    ("maximum historique", "historical maximum"),
+    ("Vent en {}", "Wind in {}"),
+    ("vent maximal", "highest wind"),
+    ("Du {} au {}", "From {} to {}"),
+    ("Degrés-jours en {}", "Degree days in {}"),
+    ("chauffage", "heating"),
+    ("climatisation", "cooling"),
+    ("Cumul de la saison en {}", "Season total in {}"),
+    ("cumul chauffage", "heating total"),
+    ("cumul climatisation", "cooling total"),
+    ("Le rapport a été généré, il n'y a pas d'original", "The report was generated and there is no original"),
+    ("Le texte du rapport n'a pas pu être téléchargé : {}", "The text of the report could not be downloaded: {}"),
+    // station
+    ("Une seule station est disponible, il n'y a rien à comparer.", "Only one station is available, there is nothing to compare."),
+    ("{} comparée à", "{} compared to"),
+    // summary
+    ("Aucune donnée dans ce rapport", "No data in this report"),
+    ("{} jours de relevés", "{} days of readings"),
+    ("Moyenne", "Mean"),
+    ("Plus haute", "Highest"),
+    ("Plus basse", "Lowest"),
+    ("Degrés-jours de chauffage", "Heating degree days"),
+    ("Degrés-jours de climatisation", "Cooling degree days"),
+    ("Total", "Total"),
+    ("Maximum", "Maximum"),
+    ("Jours > {}", "Days > {}"),
+    ("Vitesse moyenne", "Mean speed"),
+    // time periods
+    ("semaine du {}", "week of {}"),
+    ("Semaine", "Week"),
+    ("Mois", "Month"),
+    ("Année", "Year"),
+    // units
+    ("Unités", "Units"),
+    ("Température :", "Temperature:"),
+    ("Pluie :", "Rain:"),
+    ("pouces", "inches"),
+    ("Vent :", "Wind:"),
+    ("nœuds", "knots"),
+    // wind rose
+    ("Aucune donnée sur cette période", "No data over this period"),
+    ("calme\n{}%", "calm\n{}%"),
+    ("{} jours sur {}", "{} days out of {}"),
+    ("{} : {} jours", "{}: {} days"),
+];
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeSet;
+
+    use super::*;
+
+    #[test]
+    fn catalog() {
+        let mut seen = BTreeSet::new();
+        for (french, english) in CATALOG {
+            assert!(seen.insert(french), "{french:?} is twice in the catalog");
+            assert!(!english.is_empty(), "{french:?} has no English translation");
+            assert_eq!(
+                english.matches("{}").count(),
+                french.matches("{}").count(),
+                "{english:?} doesn't have the placeholders of {french:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn translate() {
+        let text = text!("{} à {}");
+        assert_eq!(Language::French.trf(text, &[&1, &2]), "1 à 2");
+        assert_eq!(Language::English.trf(text, &[&1, &2]), "1 at 2");
+        let message = Message::new(text!("Rapport illisible : {}"), &[&"ligne 3"]);
+        assert_eq!(message.to_string(), "Rapport illisible : ligne 3");
+        assert_eq!(message.tr(Language::English), "Unreadable report: ligne 3");
+    }
+}
@@ -1,11 +1,30 @@
 use egui::{Context, Window};
+use time::{Date, Month};
 
-use crate::report::{DisplayReport, Report};
+use crate::{
+    filter::ReportFilter,
+    i18n::{text, Language},
+    normals::Normals,
+    report::{DisplayReport, DisplaySession, Report},
+    units::Units,
+};
+
+/// What is restored of the [`InspectReports`] when the app is reopened.
+#[derive(Default, Debug, Clone, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+pub struct InspectSession {
+    filter: String,
+    structured: ReportFilter,
+    /// The opened windows by the name of their report, new reports shift the indexes.
+    opened: Vec<(String, DisplaySession)>,
+}
 
 #[derive(Clone)]
 pub struct InspectReports {
     parameters: Vec<Parameter>,
+    /// Matched against the title of the reports.
     filter: String,
+    structured: ReportFilter,
 }
 
 impl InspectReports {
@@ -17,22 +36,120 @@ impl InspectReports {
         Self {
             parameters,
             filter: String::new(),
+            structured: ReportFilter::default(),
+        }
+    }
+
+    /// Opens the window of the report containing `date`, returns `false` if there is none.
+    pub fn open(&mut self, reports: &[Report], date: Date) -> bool {
+        let found = self
+            .parameters
+            .iter_mut()
+            .zip(reports)
+            .find(|(_, report)| report.report.days.iter().any(|day| day.date == date));
+        match found {
+            Some((parameter, _)) => {
+                parameter.selected = true;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// The reports whose window is open.
+    pub fn opened<'a>(
+        &'a self,
+        reports: &'a [Report],
+    ) -> impl Iterator<Item = (&'a Report, &'a DisplayReport)> {
+        self.parameters
+            .iter()
+            .zip(reports)
+            .filter(|(parameter, _)| parameter.selected)
+            .map(|(parameter, report)| (report, &parameter.displaying))
+    }
+
+    /// Opens the window of the report of `month`.
+    pub fn open_month(
+        &mut self,
+        reports: &[Report],
+        year: i32,
+        month: Month,
+    ) -> Option<&mut DisplayReport> {
+        let (parameter, _) = self
+            .parameters
+            .iter_mut()
+            .zip(reports)
+            .find(|(_, report)| {
+                let date = report.report.metadata.date;
+                date.year() == year && date.month() == month
+            })?;
+        parameter.selected = true;
+        Some(&mut parameter.displaying)
+    }
+
+    pub fn close_all(&mut self) {
+        for parameter in self.parameters.iter_mut() {
+            parameter.selected = false;
+        }
+    }
+
+    pub fn session(&self, reports: &[Report]) -> InspectSession {
+        InspectSession {
+            filter: self.filter.clone(),
+            structured: self.structured.clone(),
+            opened: self
+                .opened(reports)
+                .map(|(report, displaying)| (report.name(), displaying.session()))
+                .collect(),
         }
     }
 
-    pub fn ui(&mut self, reports: &[Report], ctx: &Context) {
+    pub fn restore(&mut self, reports: &[Report], session: InspectSession) {
+        self.filter = session.filter;
+        self.structured = session.structured;
+        self.close_all();
+        for (name, displaying) in session.opened {
+            let opened = self
+                .parameters
+                .iter_mut()
+                .zip(reports)
+                .find(|(_, report)| report.name() == name);
+            if let Some((parameter, _)) = opened {
+                parameter.selected = true;
+                parameter.displaying.restore(displaying);
+            }
+        }
+    }
+
+    pub fn ui(&mut self, reports: &[Report], normals: &mut Normals, ctx: &Context) {
+        let lang = Language::get(ctx);
+        let units = Units::get(ctx);
         egui::SidePanel::right("right_panel").show(ctx, |ui| {
+            let years = reports
+                .iter()
+                .map(|report| report.report.metadata.date.year());
+            let available =
+                years.clone().min().unwrap_or_default()..=years.max().unwrap_or_default();
+            ui.vertical(|ui| {
+                ui.text_edit_singleline(&mut self.filter);
+                self.structured.ui(available, ui);
+            });
+            // a condition that can't be read doesn't hide anything, the error is shown
+            let conditions = self.structured.conditions(lang).unwrap_or_default();
+            let filtering = !self.filter.is_empty() || self.structured.is_active();
+
             // get the list of stuff we need to dislay:
             let to_display: Vec<_> = self
                 .parameters
                 .iter_mut()
                 .zip(reports)
                 .filter(|(_param, report)| {
-                    self.filter.is_empty()
+                    (self.filter.is_empty()
                         || report
-                            .name()
+                            .title(lang)
                             .to_lowercase()
-                            .contains(&self.filter.to_lowercase())
+                            .contains(&self.filter.to_lowercase()))
+                        && self.structured.matches(report, &conditions, units)
                 })
                 .collect();
 
@@ -40,15 +157,14 @@ impl InspectReports {
             let mut close_all = false;
 
             ui.vertical(|ui| {
-                ui.text_edit_singleline(&mut self.filter);
-                ui.label(format!("Total: {}", reports.len()));
-                if !self.filter.is_empty() {
-                    ui.label(format!("Après filtre: {}", to_display.len()));
+                ui.label(lang.trf(text!("Total : {}"), &[&reports.len()]));
+                if filtering {
+                    ui.label(lang.trf(text!("Après filtre : {}"), &[&to_display.len()]));
                 }
-                if ui.button("Tout sélectionner").clicked() {
+                if ui.button(lang.tr(text!("Tout sélectionner"))).clicked() {
                     select_all = true;
                 }
-                if ui.button("Tout fermer").clicked() {
+                if ui.button(lang.tr(text!("Tout fermer"))).clicked() {
                     close_all = true;
                 }
 
@@ -60,21 +176,19 @@ impl InspectReports {
                         parameter.selected = true;
                     }
                     ui.horizontal(|ui| {
-                        ui.toggle_value(&mut parameter.selected, report.name());
+                        ui.toggle_value(&mut parameter.selected, report.title(lang));
                     });
                 }
             });
 
             if close_all {
-                for parameter in self.parameters.iter_mut() {
-                    parameter.selected = false;
-                }
+                self.close_all();
             }
         });
 
         egui::CentralPanel::default().show(ctx, |_ui| {
             for (parameter, report) in self.parameters.iter_mut().zip(reports) {
-                parameter.ui(report, ctx);
+                parameter.ui(report, normals, ctx);
             }
         });
     }
@@ -87,15 +201,17 @@ pub struct Parameter {
 }
 
 impl Parameter {
-    pub fn ui(&mut self, report: &Report, ctx: &egui::Context) {
+    pub fn ui(&mut self, report: &Report, normals: &mut Normals, ctx: &egui::Context) {
         if self.selected {
             let mut still_opened = true;
-            Window::new(report.name())
+            // the window keeps its place when the language changes
+            Window::new(report.title(Language::get(ctx)))
+                .id(egui::Id::new(report.name()))
                 .default_width(800.0)
                 .default_height(500.0)
                 .open(&mut still_opened)
                 .show(ctx, |ui| {
-                    self.displaying.ui(report, ui);
+                    self.displaying.ui(report, normals, ui);
                 });
             self.selected = still_opened;
         }
@@ -1,9 +1,32 @@
+mod aggregate;
 mod app;
+mod calendar;
+mod comparison;
+mod cumulative;
 mod dashboard;
+mod dataset;
+mod diagnostics;
+mod export;
+#[cfg(all(feature = "fetch", target_arch = "wasm32"))]
+mod fetch;
+mod filter;
+mod i18n;
 mod inspect;
+mod link;
+mod merge;
+mod noaa;
+mod normals;
 mod plot;
+mod quality;
+mod range;
+mod records;
 mod report;
+mod station;
+mod summary;
+mod units;
+mod wind_rose;
 pub use app::MeteoApp;
+pub use link::DeepLink;
 use time::OffsetDateTime;
 
 fn date_to_chart(date: OffsetDateTime) -> f64 {
@@ -0,0 +1,175 @@
+use time::{macros::format_description, Date, Duration, Month};
+
+use crate::{app::View, range::chart_range, report::Tab};
+
+/// What the fragment of the URL points at on the web build, for example
+/// `#vue=globale&onglet=pluie&date=2020-09-19`.
+///
+/// Everything is optional and whatever can't be parsed is ignored, a broken link
+/// still opens the app.
+#[derive(Default, Debug, Clone, PartialEq)]
+pub struct DeepLink {
+    pub(crate) view: Option<View>,
+    /// The id of the station, the default one when it's missing.
+    pub(crate) station: Option<String>,
+    pub(crate) tab: Option<Tab>,
+    /// The first and last day shown by the plots.
+    pub(crate) range: Option<(Date, Date)>,
+    /// The year and month of the opened reports.
+    pub(crate) reports: Vec<(i32, Month)>,
+}
+
+impl DeepLink {
+    /// Parses the fragment of the URL, with or without its leading `#`.
+    pub fn parse(fragment: &str) -> Self {
+        let day = format_description!("[year]-[month]-[day]");
+        let mut link = DeepLink::default();
+        let (mut from, mut to) = (None, None);
+
+        for (key, value) in fragment
+            .trim_start_matches('#')
+            .split('&')
+            .filter_map(|pair| pair.split_once('='))
+        {
+            match key {
+                "vue" => link.view = View::from_slug(value),
+                "onglet" => link.tab = Tab::from_slug(value),
+                "station" => link.station = Some(value.to_string()),
+                "du" => from = Date::parse(value, day).ok(),
+                "au" => to = Date::parse(value, day).ok(),
+                // a single day is shown with the two weeks around it
+                "date" => {
+                    if let Ok(date) = Date::parse(value, day) {
+                        from = Some(date - Duration::days(15));
+                        to = Some(date + Duration::days(15));
+                    }
+                }
+                "rapports" => {
+                    link.reports = value.split(',').filter_map(parse_month).collect();
+                }
+                _ => log::warn!("Unknown key in the URL: {key}"),
+            }
+        }
+        link.range = match (from, to) {
+            (Some(from), Some(to)) if from <= to => Some((from, to)),
+            _ => None,
+        };
+
+        link
+    }
+
+    pub fn to_fragment(&self) -> String {
+        let day = format_description!("[year]-[month]-[day]");
+        let mut pairs = Vec::new();
+        if let Some(view) = self.view {
+            pairs.push(format!("vue={}", view.slug()));
+        }
+        if let Some(station) = &self.station {
+            pairs.push(format!("station={station}"));
+        }
+        if let Some(tab) = self.tab {
+            pairs.push(format!("onglet={}", tab.slug()));
+        }
+        if let Some((from, to)) = self.range {
+            pairs.push(format!("du={}", from.format(day).unwrap()));
+            pairs.push(format!("au={}", to.format(day).unwrap()));
+        }
+        if !self.reports.is_empty() {
+            let reports: Vec<_> = self
+                .reports
+                .iter()
+                .map(|(year, month)| format!("{year}-{:02}", *month as u8))
+                .collect();
+            pairs.push(format!("rapports={}", reports.join(",")));
+        }
+        pairs.join("&")
+    }
+
+    /// The range of the plots in chart coordinates.
+    pub(crate) fn chart_range(&self) -> Option<std::ops::RangeInclusive<f64>> {
+        self.range.map(|(from, to)| chart_range(from, to))
+    }
+}
+
+/// Parses a `2020-09` month.
+fn parse_month(value: &str) -> Option<(i32, Month)> {
+    let (year, month) = value.split_once('-')?;
+    let month = Month::try_from(month.parse::<u8>().ok()?).ok()?;
+    Some((year.parse().ok()?, month))
+}
+
+#[cfg(test)]
+mod tests {
+    use time::macros::date;
+
+    use super::*;
+
+    #[test]
+    fn round_trip() {
+        let links = [
+            DeepLink::default(),
+            DeepLink {
+                view: Some(View::Inspect),
+                station: Some(String::from("le-vigan")),
+                tab: Some(Tab::Rain),
+                range: Some((date!(2020 - 09 - 04), date!(2020 - 10 - 04))),
+                reports: vec![(2020, Month::September), (2021, Month::January)],
+            },
+            DeepLink {
+                view: Some(View::Dashboard),
+                tab: Some(Tab::WindRose),
+                ..DeepLink::default()
+            },
+        ];
+        for link in links {
+            assert_eq!(DeepLink::parse(&link.to_fragment()), link);
+            assert_eq!(DeepLink::parse(&format!("#{}", link.to_fragment())), link);
+        }
+    }
+
+    #[test]
+    fn single_day() {
+        let link = DeepLink::parse("#vue=globale&onglet=pluie&date=2020-09-19");
+        assert_eq!(link.view, Some(View::Dashboard));
+        assert_eq!(link.tab, Some(Tab::Rain));
+        assert_eq!(
+            link.range,
+            Some((date!(2020 - 09 - 04), date!(2020 - 10 - 04)))
+        );
+    }
+
+    #[test]
+    fn garbage() {
+        let fragments = [
+            "",
+            "#",
+            "&&&",
+            "===",
+            "vue",
+            "vue=",
+            "vue=nulle-part&onglet=42",
+            "du=2020-13-01&au=hier",
+            // a range going backward is dropped
+            "du=2021-01-01&au=2020-01-01",
+            "date=2020-02-30",
+            "rapports=,2020,2020-13,abc-01,-",
+            "#%%%=é&🌧️=☀️",
+        ];
+        for fragment in fragments {
+            let link = DeepLink::parse(fragment);
+            assert_eq!(link.view, None, "{fragment}");
+            assert_eq!(link.tab, None, "{fragment}");
+            assert_eq!(link.range, None, "{fragment}");
+            assert!(link.reports.is_empty(), "{fragment}");
+        }
+    }
+
+    #[test]
+    fn partly_broken() {
+        let link = DeepLink::parse("vue=calendrier&du=2020-01-01&rapports=2020-01,pas-un-mois");
+        assert_eq!(link.view, Some(View::Calendar));
+        // a range needs both of its ends
+        assert_eq!(link.range, None);
+        assert_eq!(link.reports, vec![(2020, Month::January)]);
+    }
+}
@@ -20,7 +20,7 @@ fn main() -> eframe::Result<()> {
     eframe::run_native(
         "eframe template",
         native_options,
-        Box::new(|_cc| Ok(Box::new(meteo_egui::MeteoApp::new()))),
+        Box::new(|cc| Ok(Box::new(meteo_egui::MeteoApp::new(cc)))),
     )
 }
 
@@ -36,10 +36,10 @@ fn main() {
     let web_options = eframe::WebOptions::default();
 
     wasm_bindgen_futures::spawn_local(async {
-        let document = web_sys::window()
-            .expect("No window")
-            .document()
-            .expect("No document");
+        let window = web_sys::window().expect("No window");
+        let document = window.document().expect("No document");
+        // A shared link points at a view, a tab, a period or some reports
+        let link = meteo_egui::DeepLink::parse(&window.location().hash().unwrap_or_default());
 
         let canvas = document
             .get_element_by_id("the_canvas_id")
@@ -50,7 +50,7 @@ fn main() {
             .start(
                 canvas,
                 web_options,
-                Box::new(|_cc| Ok(Box::new(meteo_egui::MeteoApp::new()))),
+                Box::new(|cc| Ok(Box::new(meteo_egui::MeteoApp::new(cc).with_link(link)))),
             )
             .await;
 
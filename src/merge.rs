@@ -0,0 +1,209 @@
+use std::collections::BTreeMap;
+
+use time::Date;
+
+use crate::{
+    i18n::Language,
+    noaa,
+    quality::Sensor,
+    report::{self, Report},
+};
+
+/// Two reports gave different values for the same day.
+#[derive(Debug, Clone)]
+pub struct Conflict {
+    pub date: Date,
+    /// The report whose values were kept.
+    pub kept: Origin,
+    pub dropped: Origin,
+    /// The sensors that disagreed.
+    pub sensors: Vec<Sensor>,
+}
+
+/// Which report a value comes from.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Origin {
+    /// A file of the reports directory, its name is the same in every language.
+    File(String),
+    /// The report of the month starting at this date, named when it's shown.
+    Month(Date),
+}
+
+impl Origin {
+    pub fn tr(&self, lang: Language) -> String {
+        match self {
+            Origin::File(name) => name.clone(),
+            Origin::Month(month) => report::month_title(*month, lang),
+        }
+    }
+}
+
+/// The copy of `other` without the days `into` already has. When both reports have
+/// a day, the values of `into` win and the disagreements end up in `conflicts`.
+pub fn missing_days(
+    into: &noaa::Report,
+    into_name: &Origin,
+    other: &noaa::Report,
+    other_name: &Origin,
+    conflicts: &mut Vec<Conflict>,
+) -> noaa::Report {
+    let known: BTreeMap<Date, usize> = into
+        .days
+        .iter()
+        .enumerate()
+        .map(|(index, day)| (day.date, index))
+        .collect();
+
+    for (index, day) in other.days.iter().enumerate() {
+        let Some(&kept) = known.get(&day.date) else {
+            continue;
+        };
+        let sensors: Vec<_> = Sensor::ALL
+            .into_iter()
+            .filter(|sensor| sensor.value(into, kept) != sensor.value(other, index))
+            .collect();
+        if !sensors.is_empty() {
+            conflicts.push(Conflict {
+                date: day.date,
+                kept: into_name.clone(),
+                dropped: other_name.clone(),
+                sensors,
+            });
+        }
+    }
+
+    let mut missing = other.clone();
+    missing.days.retain(|day| !known.contains_key(&day.date));
+    missing
+}
+
+/// Turns the reports of the same month into one. The report with the most days is
+/// preferred, then the one going the furthest, and the days it lacks are taken from
+/// the others.
+pub fn combine_month(mut reports: Vec<(String, Report)>, conflicts: &mut Vec<Conflict>) -> Report {
+    reports.sort_by_key(|(_, report)| {
+        (
+            std::cmp::Reverse(report.report.days.len()),
+            std::cmp::Reverse(report.report.last_date()),
+        )
+    });
+    let mut reports = reports.into_iter();
+    let (name, mut preferred) = reports.next().expect("a month has at least one report");
+    let name = Origin::File(name);
+
+    for (other_name, other) in reports {
+        let missing = missing_days(
+            &preferred.report,
+            &name,
+            &other.report,
+            &Origin::File(other_name),
+            conflicts,
+        );
+        if !missing.days.is_empty() {
+            preferred.report.days.extend(missing.days);
+            preferred.report.days.sort_by_key(|day| day.date);
+            // the text doesn't match the days anymore
+            preferred.original = None;
+        }
+    }
+    preferred
+}
+
+#[cfg(test)]
+mod tests {
+    use time::{macros::date, Month};
+
+    use super::*;
+    use crate::noaa::test;
+
+    fn september() -> noaa::Report {
+        test::month(2020, Month::September)
+    }
+
+    fn report(report: noaa::Report) -> Report {
+        Report {
+            original: Some("original".into()),
+            report,
+        }
+    }
+
+    #[test]
+    fn overlapping_reports() {
+        // the preferred report lacks the end of the month
+        let mut preferred = september();
+        preferred.days.retain(|day| day.date.day() <= 24);
+        // the other one has the start and the end of the month, with two values off
+        let mut other = september();
+        other
+            .days
+            .retain(|day| day.date.day() <= 10 || day.date.day() >= 25);
+        for day in other.days.iter_mut() {
+            match day.date.day() {
+                3 => day.rain += 5.0,
+                5 => day.high_temp += 1.0,
+                _ => {}
+            }
+        }
+
+        let mut conflicts = Vec::new();
+        // the order of the reports doesn't matter, the one with the most days wins
+        let combined = combine_month(
+            vec![
+                (String::from("other"), report(other)),
+                (String::from("preferred"), report(preferred)),
+            ],
+            &mut conflicts,
+        );
+
+        let expected = september();
+        assert_eq!(combined.report.days.len(), expected.days.len());
+        for (day, expected) in combined.report.days.iter().zip(&expected.days) {
+            assert_eq!(day.date, expected.date);
+            assert_eq!(day.rain, expected.rain);
+            assert_eq!(day.high_temp, expected.high_temp);
+        }
+        assert!(combined.original.is_none());
+
+        assert_eq!(conflicts.len(), 2);
+        assert_eq!(conflicts[0].date, date!(2020 - 09 - 03));
+        assert_eq!(conflicts[0].sensors, vec![Sensor::Rain]);
+        assert_eq!(conflicts[1].date, date!(2020 - 09 - 05));
+        assert_eq!(conflicts[1].sensors, vec![Sensor::HighTemp]);
+        for conflict in conflicts {
+            assert_eq!(conflict.kept, Origin::File(String::from("preferred")));
+            assert_eq!(conflict.dropped, Origin::File(String::from("other")));
+        }
+    }
+
+    #[test]
+    fn merged_reports_are_named_in_the_language_shown() {
+        let mut merged = report(september());
+        merged.report.days.retain(|day| day.date.day() <= 10);
+        let mut other = report(september());
+        other.report.days[2].rain += 5.0;
+
+        let mut conflicts = Vec::new();
+        merged.merge(&other, &mut conflicts);
+        assert_eq!(conflicts.len(), 1);
+        let conflict = &conflicts[0];
+        assert_eq!(conflict.kept, Origin::Month(date!(2020 - 09 - 01)));
+        assert_eq!(conflict.kept.tr(Language::French), "2020 - Septembre");
+        assert_eq!(conflict.dropped.tr(Language::English), "2020 - September");
+    }
+
+    #[test]
+    fn same_report_twice() {
+        let mut conflicts = Vec::new();
+        let combined = combine_month(
+            vec![
+                (String::from("mois en cours"), report(september())),
+                (String::from("09-2020"), report(september())),
+            ],
+            &mut conflicts,
+        );
+        assert_eq!(combined.report.days.len(), september().days.len());
+        // nothing was added, the text still matches
+        assert!(combined.original.is_some());
+        assert!(conflicts.is_empty());
+    }
+}
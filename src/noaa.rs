@@ -0,0 +1,275 @@
+//! The values of the NOAA monthly reports published by the stations, the way the app
+//! uses them.
+//!
+//! `meteo` parses the texts but its reports can't be built back out of their values,
+//! so they're converted once into these types, which [`crate::dataset`] stores field
+//! by field.
+//!
+//! This file is also compiled in the build script, it must only depend on `meteo`,
+//! `time` and `std`.
+
+use time::{Date, PrimitiveDateTime};
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Report {
+    pub metadata: Metadata,
+    /// Sorted by date.
+    pub days: Vec<Day>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Metadata {
+    /// The month of the report.
+    pub date: Date,
+}
+
+/// A line of the table of a report, the values are in °C, mm and km/h.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Day {
+    pub date: Date,
+    pub mean_temp: f32,
+    pub high_temp: f32,
+    pub high_temp_date: PrimitiveDateTime,
+    pub low_temp: f32,
+    pub low_temp_date: PrimitiveDateTime,
+    pub heat_deg_days: f32,
+    pub cool_deg_days: f32,
+    pub rain: f32,
+    pub avg_wind_speed: f32,
+    pub high_wind_speed: f32,
+    pub high_wind_speed_date: Option<PrimitiveDateTime>,
+    /// Like `NNE`.
+    pub dom_dir: String,
+}
+
+impl Report {
+    /// Parses the text of a report, the error is what the parser said.
+    #[allow(dead_code)] // only used by the build script, the web build and the tests
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let report: meteo::Report = text.parse().map_err(|error| format!("{error:?}"))?;
+        Ok(Self::from(report))
+    }
+
+    /// The first day of the report, the month itself without any day.
+    pub fn first_date(&self) -> Date {
+        self.days.first().map_or(self.metadata.date, |day| day.date)
+    }
+
+    pub fn last_date(&self) -> Date {
+        self.days.last().map_or(self.metadata.date, |day| day.date)
+    }
+
+    /// Adds the days of `other` this report doesn't have yet.
+    pub fn merge(&mut self, other: Report) {
+        for day in other.days {
+            if let Err(index) = self.days.binary_search_by_key(&day.date, |day| day.date) {
+                self.days.insert(index, day);
+            }
+        }
+    }
+}
+
+impl From<meteo::Report> for Report {
+    fn from(report: meteo::Report) -> Self {
+        let mut days: Vec<Day> = report
+            .days
+            .into_iter()
+            .map(|day| Day {
+                date: day.date,
+                mean_temp: day.mean_temp,
+                high_temp: day.high_temp,
+                high_temp_date: day.high_temp_date,
+                low_temp: day.low_temp,
+                low_temp_date: day.low_temp_date,
+                heat_deg_days: day.heat_deg_days,
+                cool_deg_days: day.cool_deg_days,
+                rain: day.rain,
+                avg_wind_speed: day.avg_wind_speed,
+                high_wind_speed: day.high_wind_speed,
+                high_wind_speed_date: day.high_wind_speed_date,
+                dom_dir: day.dom_dir,
+            })
+            .collect();
+        days.sort_by_key(|day| day.date);
+        Self {
+            metadata: Metadata {
+                date: report.metadata.date,
+            },
+            days,
+        }
+    }
+}
+
+/// What the header of a report says about the station, for example:
+///
+/// ```text
+/// NAME: maxou   CITY: LE VIGAN   STATE: FRANCE
+/// ELEV:   245 m  LAT:  43° 59' 23" N  LONG:   3° 36' 04" E
+/// ```
+#[derive(Default, Debug, Clone, PartialEq)]
+pub struct StationInfo {
+    pub name: Option<String>,
+    pub city: Option<String>,
+    pub state: Option<String>,
+    pub elevation: Option<String>,
+    pub latitude: Option<String>,
+    pub longitude: Option<String>,
+}
+
+impl StationInfo {
+    #[allow(dead_code)] // only used by the build script and the tests
+    const KEYS: [&'static str; 6] = ["NAME:", "CITY:", "STATE:", "ELEV:", "LAT:", "LONG:"];
+
+    /// Reads the header of the original text of a report, the missing fields stay empty.
+    #[allow(dead_code)] // only used by the build script and the tests
+    pub fn parse(original: &str) -> Self {
+        let mut info = Self::default();
+        // the header stops at the first line of the table
+        for line in original.lines().take_while(|line| !line.starts_with("---")) {
+            let mut found: Vec<(usize, &str)> = Self::KEYS
+                .iter()
+                .filter_map(|key| Some((line.find(key)?, *key)))
+                .collect();
+            found.sort_unstable();
+            for (i, (start, key)) in found.iter().enumerate() {
+                let end = found.get(i + 1).map_or(line.len(), |(next, _)| *next);
+                let value = line[start + key.len()..end].trim();
+                if value.is_empty() {
+                    continue;
+                }
+                let field = match *key {
+                    "NAME:" => &mut info.name,
+                    "CITY:" => &mut info.city,
+                    "STATE:" => &mut info.state,
+                    "ELEV:" => &mut info.elevation,
+                    "LAT:" => &mut info.latitude,
+                    _ => &mut info.longitude,
+                };
+                *field = Some(value.to_string());
+            }
+        }
+        info
+    }
+
+    /// Where the station is, on a single line.
+    pub fn location(&self) -> String {
+        [
+            self.state.as_deref(),
+            self.elevation.as_deref(),
+            self.latitude.as_deref(),
+            self.longitude.as_deref(),
+        ]
+        .into_iter()
+        .flatten()
+        .collect::<Vec<_>>()
+        .join(", ")
+    }
+}
+
+/// Reports made up for the tests of the other modules.
+#[cfg(test)]
+pub mod test {
+    use time::{Duration, Month, Time};
+
+    use super::*;
+
+    /// A day whose values all derive from `seed`, so two days differ.
+    pub fn day(date: Date, seed: f32) -> Day {
+        let at = |hour| PrimitiveDateTime::new(date, Time::from_hms(hour, 30, 0).unwrap());
+        Day {
+            date,
+            mean_temp: 10.0 + seed,
+            high_temp: 15.5 + seed,
+            high_temp_date: at(14),
+            low_temp: 4.5 + seed,
+            low_temp_date: at(5),
+            heat_deg_days: 8.3,
+            cool_deg_days: 0.0,
+            rain: seed / 2.0,
+            avg_wind_speed: 3.2 + seed,
+            high_wind_speed: 25.7 + seed,
+            high_wind_speed_date: (seed as i32 % 2 == 0).then(|| at(16)),
+            dom_dir: String::from("NNE"),
+        }
+    }
+
+    /// Every day of a month.
+    pub fn month(year: i32, month: Month) -> Report {
+        let first = Date::from_calendar_date(year, month, 1).unwrap();
+        let days = (0..month.length(year))
+            .map(|offset| {
+                day(
+                    first + Duration::days(offset as i64),
+                    f32::from(offset) % 7.0,
+                )
+            })
+            .collect();
+        Report {
+            metadata: Metadata { date: first },
+            days,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use time::{macros::date, Month};
+
+    use super::*;
+
+    #[test]
+    fn parse_a_real_report() {
+        let report = Report::parse(include_str!("../assets/reports/raw/09-2020")).unwrap();
+        assert_eq!(report.metadata.date.month(), Month::September);
+        assert_eq!(report.days.len(), 30);
+        assert_eq!(report.first_date(), date!(2020 - 09 - 01));
+        assert_eq!(report.last_date(), date!(2020 - 09 - 30));
+    }
+
+    #[test]
+    fn merge_keeps_the_days_sorted() {
+        let september = test::month(2020, Month::September);
+        let mut start = september.clone();
+        start.days.retain(|day| day.date.day() <= 10);
+        let mut rest = september.clone();
+        rest.days.retain(|day| day.date.day() > 5);
+        // the days both have stay as in `rest`
+        rest.merge(start);
+        assert_eq!(rest, september);
+    }
+
+    #[test]
+    fn parse_report_header() {
+        let info = StationInfo::parse(include_str!("../assets/reports/raw/09-2020"));
+        assert_eq!(info.name.as_deref(), Some("maxou"));
+        assert_eq!(info.city.as_deref(), Some("LE VIGAN"));
+        assert_eq!(info.state.as_deref(), Some("FRANCE"));
+        assert_eq!(info.elevation.as_deref(), Some("245 m"));
+        assert_eq!(info.latitude.as_deref(), Some("43° 59' 23\" N"));
+        assert_eq!(info.longitude.as_deref(), Some("3° 36' 04\" E"));
+        assert_eq!(
+            info.location(),
+            "FRANCE, 245 m, 43° 59' 23\" N, 3° 36' 04\" E"
+        );
+    }
+
+    #[test]
+    fn parse_partial_header() {
+        let info = StationInfo::parse(
+            "NAME:   CITY: NULLE PART\n\
+             ---\n\
+             NAME: not in the header\n",
+        );
+        assert_eq!(info.name, None);
+        assert_eq!(info.city.as_deref(), Some("NULLE PART"));
+        assert_eq!(info.elevation, None);
+    }
+
+    #[test]
+    fn dates_of_an_empty_report() {
+        let mut report = test::month(2020, Month::September);
+        report.days.clear();
+        assert_eq!(report.first_date(), date!(2020 - 09 - 01));
+        assert_eq!(report.last_date(), date!(2020 - 09 - 01));
+    }
+}
@@ -0,0 +1,155 @@
+use std::{collections::BTreeMap, ops::RangeInclusive};
+
+use egui::{DragValue, Ui};
+use time::{Date, Month};
+
+use crate::i18n::{text, Language};
+use crate::noaa;
+
+/// Number of slots in a year, the 29th of February gets its own slot so the
+/// same calendar day always lands in the same slot.
+pub const DAYS_IN_YEAR: usize = 366;
+
+/// How many days on each side of a day are averaged with it. A single day of
+/// the year only has one value per year, which is way too noisy on its own.
+const SMOOTHING: isize = 7;
+
+/// The normal temperatures of a calendar day.
+#[derive(Debug, Clone, Copy)]
+pub struct Normal {
+    pub low: f64,
+    pub mean: f64,
+    pub high: f64,
+}
+
+/// Day of year normals computed over a reference period of the whole history.
+#[derive(Clone)]
+pub struct Normals {
+    history: noaa::Report,
+    /// The years available in the history.
+    years: RangeInclusive<i32>,
+    period: RangeInclusive<i32>,
+    days: Vec<Option<Normal>>,
+    /// The normal rain of each month.
+    rain: [Option<f64>; 12],
+}
+
+impl Normals {
+    pub fn new(history: noaa::Report) -> Self {
+        let years = history.first_date().year()..=history.last_date().year();
+        let period = years.clone();
+        let days = compute(&history, &period);
+        let rain = compute_rain(&history, &period);
+        Self {
+            history,
+            years,
+            period,
+            days,
+            rain,
+        }
+    }
+
+    pub fn set_period(&mut self, period: RangeInclusive<i32>) {
+        if period != self.period {
+            self.days = compute(&self.history, &period);
+            self.rain = compute_rain(&self.history, &period);
+            self.period = period;
+        }
+    }
+
+    /// The whole history the normals are computed from.
+    pub fn history(&self) -> &noaa::Report {
+        &self.history
+    }
+
+    pub fn get(&self, date: Date) -> Option<Normal> {
+        self.days[day_of_year(date)]
+    }
+
+    pub fn monthly_rain(&self, month: Month) -> Option<f64> {
+        self.rain[month as usize - 1]
+    }
+
+    /// Lets the user pick the reference period.
+    pub fn period_ui(&mut self, ui: &mut Ui) {
+        let (mut start, mut end) = self.period.clone().into_inner();
+        ui.label(Language::get(ui.ctx()).tr(text!("Période de référence :")));
+        ui.add(DragValue::new(&mut start).range(*self.years.start()..=end));
+        ui.label("-");
+        ui.add(DragValue::new(&mut end).range(start..=*self.years.end()));
+        self.set_period(start..=end);
+    }
+}
+
+/// The slot of a date in a leap year.
+pub fn day_of_year(date: Date) -> usize {
+    let ordinal = date.ordinal() as usize - 1;
+    // skip the 29th of February on regular years
+    if !time::util::is_leap_year(date.year()) && ordinal >= 59 {
+        ordinal + 1
+    } else {
+        ordinal
+    }
+}
+
+fn compute(history: &noaa::Report, period: &RangeInclusive<i32>) -> Vec<Option<Normal>> {
+    let mut sums = vec![(0.0, 0.0, 0.0, 0_usize); DAYS_IN_YEAR];
+    for day in history
+        .days
+        .iter()
+        .filter(|day| period.contains(&day.date.year()))
+    {
+        let slot = &mut sums[day_of_year(day.date)];
+        slot.0 += day.low_temp as f64;
+        slot.1 += day.mean_temp as f64;
+        slot.2 += day.high_temp as f64;
+        slot.3 += 1;
+    }
+
+    (0..DAYS_IN_YEAR)
+        .map(|slot| {
+            let (mut low, mut mean, mut high, mut count) = (0.0, 0.0, 0.0, 0);
+            // the window wraps around the end of the year
+            for offset in -SMOOTHING..=SMOOTHING {
+                let other = (slot as isize + offset).rem_euclid(DAYS_IN_YEAR as isize);
+                let (l, m, h, c) = sums[other as usize];
+                low += l;
+                mean += m;
+                high += h;
+                count += c;
+            }
+            (count > 0).then(|| Normal {
+                low: low / count as f64,
+                mean: mean / count as f64,
+                high: high / count as f64,
+            })
+        })
+        .collect()
+}
+
+fn compute_rain(history: &noaa::Report, period: &RangeInclusive<i32>) -> [Option<f64>; 12] {
+    let mut months: BTreeMap<(i32, u8), (f64, u8)> = BTreeMap::new();
+    for day in history
+        .days
+        .iter()
+        .filter(|day| period.contains(&day.date.year()))
+    {
+        let month = months
+            .entry((day.date.year(), day.date.month() as u8))
+            .or_default();
+        month.0 += day.rain as f64;
+        month.1 += 1;
+    }
+
+    let mut sums = [(0.0, 0); 12];
+    for ((year, month), (rain, days)) in months {
+        let month = Month::try_from(month).unwrap();
+        // an incomplete month would drag the normal down
+        if days == month.length(year) {
+            let sum = &mut sums[month as usize - 1];
+            sum.0 += rain;
+            sum.1 += 1;
+        }
+    }
+    sums.map(|(rain, count)| (count > 0).then(|| rain / count as f64))
+}
@@ -1,10 +1,19 @@
 use std::{ops::RangeInclusive, sync::Arc};
 
-use egui_plot::{AxisHints, CoordinatesFormatter, GridInput, GridMark, Legend, Plot, PlotPoint};
-use meteo::Report;
-use time::{macros::format_description, Date, Duration, Month, OffsetDateTime, Time};
+use crate::noaa::Report;
+use egui::{Color32, Stroke};
+use egui_plot::{
+    AxisHints, CoordinatesFormatter, GridInput, GridMark, Legend, Plot, PlotPoint, PlotUi, Polygon,
+};
+use time::{
+    macros::format_description, Date, Duration, Month, OffsetDateTime, PrimitiveDateTime, Time,
+};
 
-use crate::{date_from_chart, date_to_chart};
+use crate::{
+    aggregate::Aggregation,
+    date_from_chart, date_to_chart,
+    i18n::{text, Language},
+};
 
 #[allow(clippy::collapsible_if)]
 fn x_grid(input: GridInput) -> Vec<GridMark> {
@@ -206,52 +215,102 @@ fn x_grid(input: GridInput) -> Vec<GridMark> {
     marks
 }
 
+/// How much the days should be grouped to stay readable over the `start..end` range
+/// of a plot against the time, for its lines and bars alike. It follows the zoom
+/// levels of [`x_grid`].
+pub fn aggregation_for_range(start: f64, end: f64) -> Aggregation {
+    let (Some(start), Some(end)) = (date_from_chart(start), date_from_chart(end)) else {
+        return Aggregation::Year;
+    };
+    let days = (end - start).whole_days();
+    if days > 365 * 20 {
+        Aggregation::Year
+    } else if days > 365 * 3 {
+        Aggregation::Month
+    } else if days > 30 * 3 {
+        Aggregation::Week
+    } else {
+        Aggregation::Day
+    }
+}
+
+/// Fills the space between two lines of the same grouped days, like the lowest and
+/// highest values of each period around their mean. Both must come from
+/// [`level_of_detail`](crate::aggregate::level_of_detail) with the same aggregation
+/// over the same days.
+pub fn band(
+    ui: &mut PlotUi,
+    name: &str,
+    color: Color32,
+    lower: &[Vec<[f64; 2]>],
+    upper: &[Vec<[f64; 2]>],
+) {
+    for (lower, upper) in lower.iter().zip(upper) {
+        // the polygons must be convex, one per period is
+        for (low, high) in lower.windows(2).zip(upper.windows(2)) {
+            ui.polygon(
+                Polygon::new(name, vec![low[0], low[1], high[1], high[0]])
+                    .fill_color(color.gamma_multiply(0.15))
+                    .stroke(Stroke::NONE)
+                    .allow_hover(false),
+            );
+        }
+    }
+}
+
+/// The period displayed when a report is opened: its last 60 days.
+pub fn default_range(report: &Report) -> (OffsetDateTime, OffsetDateTime) {
+    let mut start = report
+        .first_date()
+        .with_time(Time::from_hms(0, 0, 0).unwrap())
+        .assume_utc();
+    let end = report
+        .last_date()
+        .with_time(Time::from_hms(23, 59, 59).unwrap())
+        .assume_utc();
+    if (end - start).whole_days() > 60 {
+        start = end - Duration::DAY * 60;
+    }
+    (start, end)
+}
+
 pub fn create_plot_time<'a>(
     name: &'a str,
+    lang: Language,
     report: &Report,
     formatter: impl Fn(f64) -> String + 'static,
 ) -> Plot<'a> {
-    let time_formatter = |mark: GridMark, _range: &RangeInclusive<f64>| {
+    let time_formatter = move |mark: GridMark, _range: &RangeInclusive<f64>| {
         let step = date_from_chart(mark.step_size).unwrap();
         let step = step - OffsetDateTime::from_unix_timestamp(0).unwrap();
         let days = step.whole_days();
-        let format = if days > 364 {
-            format_description!("[year]")
+        let date = date_from_chart(mark.value).unwrap();
+        if days > 364 {
+            date.year().to_string()
         } else if days > 29 {
-            format_description!("[year]/[month]")
+            let format = match lang {
+                Language::French => format_description!("[month]/[year]"),
+                Language::English => format_description!("[year]-[month]"),
+            };
+            date.format(format).unwrap()
         } else if days > 0 {
-            format_description!("[year]/[month]/[day]")
+            lang.date(date.date())
         } else {
-            format_description!("[year]/[month]/[day] - [hour]:[minute]")
-        };
-        date_from_chart(mark.value).unwrap().format(format).unwrap()
+            let date = PrimitiveDateTime::new(date.date(), date.time());
+            lang.date_time(date)
+        }
     };
 
     let format_plot_point = Arc::new(move |point: &PlotPoint| {
         let date = date_from_chart(point.x)
-            .map(|date| {
-                date.format(format_description!(
-                    "[year]/[month]/[day] - [hour]:[minute]"
-                ))
-                .unwrap()
-            })
+            .map(|date| lang.date_time(PrimitiveDateTime::new(date.date(), date.time())))
             .unwrap_or(String::from(""));
         format!("{}\n{}", date, formatter(point.y))
     });
 
     let fmt = format_plot_point.clone();
 
-    let mut start = report
-        .first_date()
-        .with_time(Time::from_hms(0, 0, 0).unwrap())
-        .assume_utc();
-    let end = report
-        .last_date()
-        .with_time(Time::from_hms(23, 59, 59).unwrap())
-        .assume_utc();
-    if (end - start).whole_days() > 60 {
-        start = end - Duration::DAY * 60;
-    }
+    let (start, end) = default_range(report);
     Plot::new(name)
         .legend(Legend::default())
         .default_x_bounds(date_to_chart(start), date_to_chart(end))
@@ -260,8 +319,81 @@ pub fn create_plot_time<'a>(
             CoordinatesFormatter::new(move |point, _| fmt(point)),
         )
         .custom_x_axes(vec![AxisHints::new_x()
-            .label("Date")
+            .label(lang.tr(text!("Date")))
             .formatter(time_formatter)])
         .x_grid_spacer(x_grid)
         .label_formatter(move |_, point| format_plot_point(point))
 }
+
+/// Any leap year works, it's only used to place the days of the year on the x axis.
+pub const LEAP_YEAR: i32 = 2000;
+
+/// The date a day of the year slot (see [`crate::normals::day_of_year`]) stands for.
+pub fn date_of_slot(slot: f64) -> Option<Date> {
+    let ordinal = u16::try_from(slot.round() as i64 + 1).ok()?;
+    Date::from_ordinal_date(LEAP_YEAR, ordinal).ok()
+}
+
+fn month_grid(_input: GridInput) -> Vec<GridMark> {
+    (1..=12)
+        .map(|month| {
+            let month = Month::try_from(month).unwrap();
+            let date = Date::from_calendar_date(LEAP_YEAR, month, 1).unwrap();
+            GridMark {
+                value: (date.ordinal() - 1) as f64,
+                step_size: 30.0,
+            }
+        })
+        .collect()
+}
+
+/// A plot where the x axis is the day of the year, so several years can be drawn on
+/// top of each other.
+pub fn create_plot_day_of_year(
+    name: &str,
+    lang: Language,
+    formatter: impl Fn(f64) -> String + 'static,
+) -> Plot<'_> {
+    let day_formatter = move |mark: GridMark, _range: &RangeInclusive<f64>| {
+        date_of_slot(mark.value)
+            .map(|date| lang.month(date.month()).to_string())
+            .unwrap_or_default()
+    };
+
+    let format_plot_point = Arc::new(move |point: &PlotPoint| {
+        let date = date_of_slot(point.x)
+            .map(|date| format!("{} {}", date.day(), lang.month(date.month())))
+            .unwrap_or_default();
+        format!("{date}\n{}", formatter(point.y))
+    });
+    let fmt = format_plot_point.clone();
+
+    Plot::new(name)
+        .legend(Legend::default())
+        .coordinates_formatter(
+            egui_plot::Corner::LeftBottom,
+            CoordinatesFormatter::new(move |point, _| fmt(point)),
+        )
+        .custom_x_axes(vec![AxisHints::new_x()
+            .label(lang.tr(text!("Jour de l'année")))
+            .formatter(day_formatter)])
+        .x_grid_spacer(month_grid)
+        .label_formatter(move |name, point| format!("{name}\n{}", format_plot_point(point)))
+}
+
+/// A plot where the x axis is the day of the month.
+pub fn create_plot_day_of_month(
+    name: &str,
+    lang: Language,
+    formatter: impl Fn(f64) -> String + 'static,
+) -> Plot<'_> {
+    Plot::new(name)
+        .legend(Legend::default())
+        .custom_x_axes(vec![
+            AxisHints::new_x().label(lang.tr(text!("Jour du mois")))
+        ])
+        .label_formatter(move |name, point| {
+            let day = lang.trf(text!("le {}"), &[&point.x.round()]);
+            format!("{name}\n{day}\n{}", formatter(point.y))
+        })
+}
@@ -0,0 +1,257 @@
+use time::{Date, Duration};
+
+use crate::{
+    i18n::{text, Language, Text},
+    noaa,
+    units::{Quantity, Units},
+};
+
+/// How many identical days in a row make a sensor look stuck.
+const FLAT_DAYS: usize = 5;
+
+/// A measure of the reports that can go wrong.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Sensor {
+    MeanTemp,
+    HighTemp,
+    LowTemp,
+    Rain,
+    AvgWind,
+    HighWind,
+}
+
+impl Sensor {
+    pub const ALL: [Sensor; 6] = [
+        Sensor::MeanTemp,
+        Sensor::HighTemp,
+        Sensor::LowTemp,
+        Sensor::Rain,
+        Sensor::AvgWind,
+        Sensor::HighWind,
+    ];
+
+    pub fn name(self) -> Text {
+        match self {
+            Sensor::MeanTemp => text!("température moyenne"),
+            Sensor::HighTemp => text!("température maximale"),
+            Sensor::LowTemp => text!("température minimale"),
+            Sensor::Rain => text!("pluie"),
+            Sensor::AvgWind => text!("vent moyen"),
+            Sensor::HighWind => text!("rafales"),
+        }
+    }
+
+    pub fn quantity(self) -> Quantity {
+        match self {
+            Sensor::MeanTemp | Sensor::HighTemp | Sensor::LowTemp => Quantity::Temperature,
+            Sensor::Rain => Quantity::Rain,
+            Sensor::AvgWind | Sensor::HighWind => Quantity::Wind,
+        }
+    }
+
+    pub fn value(self, report: &noaa::Report, index: usize) -> f64 {
+        let day = &report.days[index];
+        match self {
+            Sensor::MeanTemp => day.mean_temp as f64,
+            Sensor::HighTemp => day.high_temp as f64,
+            Sensor::LowTemp => day.low_temp as f64,
+            Sensor::Rain => day.rain as f64,
+            Sensor::AvgWind => day.avg_wind_speed as f64,
+            Sensor::HighWind => day.high_wind_speed as f64,
+        }
+    }
+
+    /// The values the station can physically measure in Le Vigan.
+    fn possible(self, value: f64) -> bool {
+        match self {
+            Sensor::MeanTemp | Sensor::HighTemp | Sensor::LowTemp => {
+                (-40.0..=50.0).contains(&value)
+            }
+            Sensor::Rain => (0.0..=500.0).contains(&value),
+            Sensor::AvgWind | Sensor::HighWind => (0.0..=250.0).contains(&value),
+        }
+    }
+
+    /// It can stay dry for weeks, a stuck rain gauge can't be told apart from it.
+    fn can_flat_line(self) -> bool {
+        self != Sensor::Rain
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Problem {
+    /// Days missing from the report.
+    Gap,
+    /// The sensor reported the same value for days.
+    FlatLine(Sensor, f64),
+    Impossible(Sensor, f64),
+    /// The minimum is above the maximum, or the mean isn't between them.
+    Inconsistent,
+    /// The day is an exact copy of the previous one.
+    Repeated,
+}
+
+impl Problem {
+    pub fn describe(self, lang: Language, units: Units) -> String {
+        match self {
+            Problem::Gap => String::from(lang.tr(text!("Jours manquants"))),
+            Problem::FlatLine(sensor, value) => lang.trf(
+                text!("{} bloquée à {}"),
+                &[
+                    &capitalize(lang.tr(sensor.name())),
+                    &units.format(lang, sensor.quantity(), value),
+                ],
+            ),
+            Problem::Impossible(sensor, value) => lang.trf(
+                text!("{} impossible : {}"),
+                &[
+                    &capitalize(lang.tr(sensor.name())),
+                    &units.format(lang, sensor.quantity(), value),
+                ],
+            ),
+            Problem::Inconsistent => String::from(lang.tr(text!(
+                "Températures incohérentes entre minimum, moyenne et maximum"
+            ))),
+            Problem::Repeated => String::from(lang.tr(text!("Jour identique au précédent"))),
+        }
+    }
+}
+
+fn capitalize(name: &str) -> String {
+    let mut chars = name.chars();
+    chars
+        .next()
+        .map(|first| first.to_uppercase().chain(chars).collect())
+        .unwrap_or_default()
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct Issue {
+    pub problem: Problem,
+    pub start: Date,
+    pub end: Date,
+}
+
+impl Issue {
+    pub fn period(&self, lang: Language) -> String {
+        if self.start == self.end {
+            lang.trf(text!("le {}"), &[&lang.date(self.start)])
+        } else {
+            lang.trf(
+                text!("du {} au {}"),
+                &[&lang.date(self.start), &lang.date(self.end)],
+            )
+        }
+    }
+}
+
+/// The suspicious days of a report.
+#[derive(Debug, Clone, Default)]
+pub struct Quality {
+    issues: Vec<Issue>,
+    /// The index of the suspicious days and the sensor at fault.
+    flagged: Vec<(usize, Sensor)>,
+}
+
+impl Quality {
+    pub fn new(report: &noaa::Report) -> Self {
+        let mut quality = Quality::default();
+        let mut order: Vec<usize> = (0..report.days.len()).collect();
+        order.sort_by_key(|index| report.days[*index].date);
+
+        for pair in order.windows(2) {
+            let (previous, current) = (&report.days[pair[0]], &report.days[pair[1]]);
+            if current.date - previous.date > Duration::DAY {
+                quality.issues.push(Issue {
+                    problem: Problem::Gap,
+                    start: previous.date + Duration::DAY,
+                    end: current.date - Duration::DAY,
+                });
+            }
+            let repeated = Sensor::ALL
+                .into_iter()
+                .all(|sensor| sensor.value(report, pair[0]) == sensor.value(report, pair[1]));
+            if repeated && current.date - previous.date == Duration::DAY {
+                quality.day_issue(Problem::Repeated, current.date);
+                quality.flagged.push((pair[1], Sensor::MeanTemp));
+            }
+        }
+
+        for &index in order.iter() {
+            let day = &report.days[index];
+            for sensor in Sensor::ALL {
+                let value = sensor.value(report, index);
+                if !sensor.possible(value) {
+                    quality.day_issue(Problem::Impossible(sensor, value), day.date);
+                    quality.flagged.push((index, sensor));
+                }
+            }
+            let (low, mean, high) = (day.low_temp, day.mean_temp, day.high_temp);
+            if low > high || mean < low || mean > high {
+                quality.day_issue(Problem::Inconsistent, day.date);
+                quality.flagged.push((index, Sensor::MeanTemp));
+            }
+        }
+
+        for sensor in Sensor::ALL
+            .into_iter()
+            .filter(|sensor| sensor.can_flat_line())
+        {
+            quality.flat_lines(report, &order, sensor);
+        }
+
+        quality.issues.sort_by_key(|issue| issue.start);
+        quality
+    }
+
+    fn day_issue(&mut self, problem: Problem, date: Date) {
+        self.issues.push(Issue {
+            problem,
+            start: date,
+            end: date,
+        });
+    }
+
+    /// Finds the runs of consecutive days where `sensor` didn't move.
+    fn flat_lines(&mut self, report: &noaa::Report, order: &[usize], sensor: Sensor) {
+        let mut run: Vec<usize> = Vec::new();
+        // the extra `None` closes the last run
+        for index in order.iter().copied().map(Some).chain([None]) {
+            let continues = match (index, run.last()) {
+                (Some(index), Some(&last)) => {
+                    report.days[index].date - report.days[last].date == Duration::DAY
+                        && sensor.value(report, index) == sensor.value(report, last)
+                }
+                _ => false,
+            };
+            if continues {
+                run.extend(index);
+                continue;
+            }
+            if run.len() >= FLAT_DAYS {
+                let value = sensor.value(report, run[0]);
+                self.issues.push(Issue {
+                    problem: Problem::FlatLine(sensor, value),
+                    start: report.days[run[0]].date,
+                    end: report.days[run[run.len() - 1]].date,
+                });
+                self.flagged
+                    .extend(run.iter().map(|index| (*index, sensor)));
+            }
+            run.clear();
+            run.extend(index);
+        }
+    }
+
+    pub fn issues(&self) -> &[Issue] {
+        &self.issues
+    }
+
+    /// The suspicious days of a sensor.
+    pub fn flagged(&self, sensor: Sensor) -> impl Iterator<Item = usize> + '_ {
+        self.flagged
+            .iter()
+            .filter(move |(_, flagged)| *flagged == sensor)
+            .map(|(index, _)| *index)
+    }
+}
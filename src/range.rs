@@ -0,0 +1,121 @@
+use std::ops::RangeInclusive;
+
+use egui::{TextEdit, Ui};
+use time::{Date, Duration, OffsetDateTime};
+
+use crate::{
+    date_from_chart, date_to_chart,
+    i18n::{text, Language, Text},
+    noaa,
+};
+
+#[derive(Debug, Clone, Copy)]
+enum Preset {
+    Week,
+    Month,
+    Year,
+    All,
+}
+
+impl Preset {
+    const ALL: [Preset; 4] = [Preset::Week, Preset::Month, Preset::Year, Preset::All];
+
+    fn name(self) -> Text {
+        match self {
+            Preset::Week => text!("7 jours"),
+            Preset::Month => text!("30 jours"),
+            Preset::Year => text!("1 an"),
+            Preset::All => text!("tout"),
+        }
+    }
+
+    /// The period of the preset, it always ends with the last day of the report.
+    fn range(self, report: &noaa::Report) -> (Date, Date) {
+        let end = report.last_date();
+        let start = match self {
+            Preset::Week => end - Duration::days(6),
+            Preset::Month => end - Duration::days(29),
+            Preset::Year => end - Duration::days(364),
+            Preset::All => report.first_date(),
+        };
+        (start.max(report.first_date()), end)
+    }
+}
+
+/// Lets the user pick the period shown by the plots against the time.
+#[derive(Default, Debug, Clone)]
+pub struct RangeBar {
+    start: String,
+    end: String,
+}
+
+impl RangeBar {
+    /// `visible` is the period currently shown. Returns the period the user asked
+    /// for in chart coordinates.
+    pub fn ui(
+        &mut self,
+        report: &noaa::Report,
+        visible: Option<&RangeInclusive<f64>>,
+        ui: &mut Ui,
+    ) -> Option<RangeInclusive<f64>> {
+        let lang = Language::get(ui.ctx());
+        let format = lang.date_format();
+        let mut requested = None;
+
+        ui.horizontal(|ui| {
+            for preset in Preset::ALL {
+                if ui.button(lang.tr(preset.name())).clicked() {
+                    requested = Some(preset.range(report));
+                }
+            }
+            ui.separator();
+
+            let start_id = ui.id().with("range start");
+            let end_id = ui.id().with("range end");
+            // the fields follow the plot unless the user is typing in them
+            let editing =
+                ui.memory(|memory| memory.has_focus(start_id) || memory.has_focus(end_id));
+            if let (false, Some(visible)) = (editing, visible) {
+                if let (Some(start), Some(end)) = (
+                    date_from_chart(*visible.start()),
+                    date_from_chart(*visible.end()),
+                ) {
+                    self.start = start.date().format(format).unwrap();
+                    self.end = end.date().format(format).unwrap();
+                }
+            }
+
+            ui.label(lang.tr(text!("Du")));
+            let start = ui.add(
+                TextEdit::singleline(&mut self.start)
+                    .id(start_id)
+                    .desired_width(80.0),
+            );
+            ui.label(lang.tr(text!("au")));
+            let end = ui.add(
+                TextEdit::singleline(&mut self.end)
+                    .id(end_id)
+                    .desired_width(80.0),
+            );
+            if start.lost_focus() || end.lost_focus() {
+                match (
+                    Date::parse(&self.start, format),
+                    Date::parse(&self.end, format),
+                ) {
+                    (Ok(start), Ok(end)) if start <= end => requested = Some((start, end)),
+                    // the fields are reset to the visible period on the next frame
+                    _ => (),
+                }
+            }
+        });
+
+        requested.map(|(start, end)| chart_range(start, end))
+    }
+}
+
+/// The whole days from `start` to `end` in chart coordinates.
+pub fn chart_range(start: Date, end: Date) -> RangeInclusive<f64> {
+    let start: OffsetDateTime = start.midnight().assume_utc();
+    let end: OffsetDateTime = end.with_hms(23, 59, 59).unwrap().assume_utc();
+    date_to_chart(start)..=date_to_chart(end)
+}
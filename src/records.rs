@@ -0,0 +1,226 @@
+use egui::{Context, Grid, RichText, Ui};
+use time::{Date, Month};
+
+use crate::{
+    i18n::{text, Language, Text},
+    noaa,
+    units::{Quantity, Units},
+};
+
+#[derive(Debug, Clone, Copy)]
+enum Kind {
+    HighestMax,
+    LowestMin,
+    HighestMean,
+    LowestMean,
+    Wettest,
+    StrongestGust,
+    WarmestNight,
+    ColdestDay,
+}
+
+impl Kind {
+    const ALL: [Kind; 8] = [
+        Kind::HighestMax,
+        Kind::LowestMin,
+        Kind::HighestMean,
+        Kind::LowestMean,
+        Kind::Wettest,
+        Kind::StrongestGust,
+        Kind::WarmestNight,
+        Kind::ColdestDay,
+    ];
+
+    fn name(self) -> Text {
+        match self {
+            Kind::HighestMax => text!("Température la plus haute"),
+            Kind::LowestMin => text!("Température la plus basse"),
+            Kind::HighestMean => text!("Journée la plus chaude en moyenne"),
+            Kind::LowestMean => text!("Journée la plus froide en moyenne"),
+            Kind::Wettest => text!("Journée la plus pluvieuse"),
+            Kind::StrongestGust => text!("Rafale la plus forte"),
+            Kind::WarmestNight => text!("Nuit la plus chaude"),
+            Kind::ColdestDay => text!("Journée la plus froide"),
+        }
+    }
+
+    fn quantity(self) -> Quantity {
+        match self {
+            Kind::Wettest => Quantity::Rain,
+            Kind::StrongestGust => Quantity::Wind,
+            _ => Quantity::Temperature,
+        }
+    }
+
+    fn value(self, report: &noaa::Report, index: usize) -> f64 {
+        let day = &report.days[index];
+        match self {
+            Kind::HighestMax => day.high_temp as f64,
+            Kind::LowestMin => day.low_temp as f64,
+            Kind::HighestMean | Kind::LowestMean => day.mean_temp as f64,
+            Kind::Wettest => day.rain as f64,
+            Kind::StrongestGust => day.high_wind_speed as f64,
+            // the warmest night is the day with the highest minimum
+            Kind::WarmestNight => day.low_temp as f64,
+            // and the coldest day the one with the lowest maximum
+            Kind::ColdestDay => day.high_temp as f64,
+        }
+    }
+
+    fn beats(self, value: f64, record: f64) -> bool {
+        match self {
+            Kind::HighestMax
+            | Kind::HighestMean
+            | Kind::Wettest
+            | Kind::StrongestGust
+            | Kind::WarmestNight => value > record,
+            Kind::LowestMin | Kind::LowestMean | Kind::ColdestDay => value < record,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Holder {
+    value: f64,
+    date: Date,
+}
+
+#[derive(Default, Debug, Clone, Copy)]
+struct Record {
+    current: Option<Holder>,
+    /// The record `current` broke.
+    previous: Option<Holder>,
+}
+
+impl Record {
+    fn challenge(&mut self, kind: Kind, holder: Holder) {
+        if self
+            .current
+            .is_none_or(|current| kind.beats(holder.value, current.value))
+        {
+            self.previous = self.current.replace(holder);
+        }
+    }
+}
+
+/// The all-time and monthly records of the history.
+#[derive(Debug, Clone)]
+pub struct Records {
+    all: [Record; Kind::ALL.len()],
+    months: [[Record; Kind::ALL.len()]; 12],
+    /// `None` shows the all-time records.
+    month: Option<Month>,
+}
+
+impl Records {
+    pub fn new(history: &noaa::Report) -> Self {
+        let mut records = Self {
+            all: Default::default(),
+            months: Default::default(),
+            month: None,
+        };
+
+        // The records must be challenged in chronological order to know which one
+        // was broken by whom.
+        let mut order: Vec<usize> = (0..history.days.len()).collect();
+        order.sort_by_key(|index| history.days[*index].date);
+
+        for index in order {
+            let date = history.days[index].date;
+            let month = &mut records.months[date.month() as usize - 1];
+            for (i, kind) in Kind::ALL.into_iter().enumerate() {
+                let holder = Holder {
+                    value: kind.value(history, index),
+                    date,
+                };
+                records.all[i].challenge(kind, holder);
+                month[i].challenge(kind, holder);
+            }
+        }
+
+        records
+    }
+
+    /// Returns the date of the record the user clicked on.
+    pub fn ui(&mut self, ctx: &Context) -> Option<Date> {
+        let lang = Language::get(ctx);
+        egui::CentralPanel::default()
+            .show(ctx, |ui| {
+                ui.horizontal_wrapped(|ui| {
+                    ui.selectable_value(&mut self.month, None, lang.tr(text!("Tous les mois")));
+                    for month in 1..=12 {
+                        let month = Month::try_from(month).unwrap();
+                        ui.selectable_value(&mut self.month, Some(month), lang.month(month));
+                    }
+                });
+                ui.separator();
+
+                let records = match self.month {
+                    None => &self.all,
+                    Some(month) => &self.months[month as usize - 1],
+                };
+                egui::ScrollArea::vertical()
+                    .show(ui, |ui| records_grid(records, ui))
+                    .inner
+            })
+            .inner
+    }
+}
+
+fn records_grid(records: &[Record; Kind::ALL.len()], ui: &mut Ui) -> Option<Date> {
+    let lang = Language::get(ui.ctx());
+    let units = Units::get(ui.ctx());
+    let mut clicked = None;
+
+    Grid::new("records")
+        .num_columns(4)
+        .striped(true)
+        .spacing([20.0, 6.0])
+        .show(ui, |ui| {
+            ui.label(RichText::new(lang.tr(text!("Record"))).strong());
+            ui.label(RichText::new(lang.tr(text!("Valeur"))).strong());
+            ui.label(RichText::new(lang.tr(text!("Établi le"))).strong());
+            ui.label(RichText::new(lang.tr(text!("Record précédent"))).strong());
+            ui.end_row();
+
+            for (kind, record) in Kind::ALL.into_iter().zip(records) {
+                ui.label(lang.tr(kind.name()));
+                let Some(current) = record.current else {
+                    ui.label("---");
+                    ui.end_row();
+                    continue;
+                };
+                ui.label(units.format(lang, kind.quantity(), current.value));
+                if ui
+                    .link(lang.date(current.date))
+                    .on_hover_text(lang.tr(text!("Inspecter le rapport")))
+                    .clicked()
+                {
+                    clicked = Some(current.date);
+                }
+                match record.previous {
+                    Some(previous) => {
+                        ui.horizontal(|ui| {
+                            ui.label(lang.trf(
+                                text!("{} le"),
+                                &[&units.format(lang, kind.quantity(), previous.value)],
+                            ));
+                            if ui
+                                .link(lang.date(previous.date))
+                                .on_hover_text(lang.tr(text!("Inspecter le rapport")))
+                                .clicked()
+                            {
+                                clicked = Some(previous.date);
+                            }
+                        });
+                    }
+                    None => {
+                        ui.label("---");
+                    }
+                }
+                ui.end_row();
+            }
+        });
+
+    clicked
+}
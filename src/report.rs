@@ -1,12 +1,33 @@
-use egui::{Color32, RichText, Ui};
-use egui_plot::{AxisHints, Line};
-use time::Month;
+use std::ops::RangeInclusive;
 
-use crate::{date_to_chart, plot::create_plot_time};
+use egui::{Align, Align2, Color32, FontId, Grid, Layout, Rect, RichText, Ui, Vec2, Vec2b};
+use egui_plot::{
+    AxisHints, Bar, BarChart, HLine, Line, LineStyle, MarkerShape, Plot, PlotBounds, PlotUi, Points,
+};
+use time::{macros::format_description, Date, Duration, Month};
+
+use crate::{
+    aggregate::{aggregate, level_of_detail, Aggregation, Reduce},
+    comparison::Comparison,
+    cumulative::YearToDate,
+    date_from_chart, date_to_chart, export,
+    i18n::{text, Language},
+    merge::{self, Conflict, Origin},
+    noaa,
+    normals::Normals,
+    plot::{aggregation_for_range, band, create_plot_day_of_year, create_plot_time, default_range},
+    quality::{Problem, Quality, Sensor},
+    range::RangeBar,
+    summary::Summary,
+    units::{Quantity, Units},
+    wind_rose::{WindRose, WindSpeed},
+};
 
 pub struct Report {
+    /// Where the text the report was parsed from can be downloaded, only the web build
+    /// can. `None` once the report is merged with others.
     pub original: Option<String>,
-    pub report: meteo::Report,
+    pub report: noaa::Report,
 }
 
 impl Clone for Report {
@@ -19,207 +40,1199 @@ impl Clone for Report {
 }
 
 impl Report {
-    pub fn original(original: String) -> Self {
-        let report = original.parse().unwrap();
-        Self {
-            original: Some(original),
-            report,
+    /// Adds the days of `other` this report doesn't have yet, see
+    /// [`merge::missing_days`]. The result doesn't match any original text anymore.
+    pub fn merge(&mut self, other: &Self, conflicts: &mut Vec<Conflict>) {
+        let missing = merge::missing_days(
+            &self.report,
+            &Origin::Month(self.report.metadata.date),
+            &other.report,
+            &Origin::Month(other.report.metadata.date),
+            conflicts,
+        );
+        if !missing.days.is_empty() {
+            self.report.merge(missing);
+            self.original = None;
         }
     }
 
-    pub fn merge(&self, other: &Self) -> Self {
-        let mut report = self.report.clone();
-        report.merge(other.report.clone()).unwrap();
+    /// Identifies the report in the saved sessions and the diagnostics, it doesn't
+    /// change with the language.
+    pub fn name(&self) -> String {
+        self.title(Language::French)
+    }
 
-        Self {
-            original: None,
-            report,
-        }
+    /// How the report is named to the user.
+    pub fn title(&self, lang: Language) -> String {
+        month_title(self.report.metadata.date, lang)
     }
+}
 
-    pub fn name(&self) -> String {
-        let date = self.report.metadata.date;
-        let month = match date.month() {
-            Month::January => "Janvier",
-            Month::February => "Février",
-            Month::March => "Mars",
-            Month::April => "Avril",
-            Month::May => "Mai",
-            Month::June => "Juin",
-            Month::July => "Juillet",
-            Month::August => "Aout",
-            Month::September => "Septembre",
-            Month::October => "Octobre",
-            Month::November => "Novembre",
-            Month::December => "Décembre",
+/// How the report of the month of `date` is named to the user.
+pub fn month_title(date: Date, lang: Language) -> String {
+    format!("{} - {}", date.year(), lang.month(date.month()))
+}
+
+/// Circles the suspicious values found by the quality checks.
+fn flag(ui: &mut PlotUi, points: Vec<[f64; 2]>) {
+    let lang = Language::get(ui.ctx());
+    ui.points(
+        Points::new(lang.tr(text!("données suspectes")), points)
+            .shape(MarkerShape::Circle)
+            .radius(6.0)
+            .filled(false)
+            .color(Color32::from_rgb(255, 140, 0))
+            .name(lang.tr(text!("données suspectes"))),
+    );
+}
+
+/// Shows everything the report says about one of its days.
+pub fn day_details(ui: &mut Ui, report: &noaa::Report, index: usize) {
+    let day = &report.days[index];
+    let time = format_description!("[hour]:[minute]");
+    let lang = Language::get(ui.ctx());
+    let units = Units::get(ui.ctx());
+    let temperature = |value: f32| units.format(lang, Quantity::Temperature, value as f64);
+    let wind = |value: f32| units.format(lang, Quantity::Wind, value as f64);
+
+    ui.label(RichText::new(lang.date(day.date)).strong());
+    Grid::new("day details").num_columns(2).show(ui, |ui| {
+        ui.label(lang.tr(text!("Température moyenne")));
+        ui.label(temperature(day.mean_temp));
+        ui.end_row();
+        ui.label(lang.tr(text!("Température maximale")));
+        ui.label(lang.trf(
+            text!("{} à {}"),
+            &[
+                &temperature(day.high_temp),
+                &day.high_temp_date.format(time).unwrap(),
+            ],
+        ));
+        ui.end_row();
+        ui.label(lang.tr(text!("Température minimale")));
+        ui.label(lang.trf(
+            text!("{} à {}"),
+            &[
+                &temperature(day.low_temp),
+                &day.low_temp_date.format(time).unwrap(),
+            ],
+        ));
+        ui.end_row();
+        ui.label(lang.tr(text!("Degrés-jours")));
+        let degree_days = |value: f32| units.convert(Quantity::TemperatureDifference, value as f64);
+        ui.label(lang.trf(
+            text!("{} chauffage, {} climatisation"),
+            &[
+                &lang.number(degree_days(day.heat_deg_days), 1),
+                &lang.number(degree_days(day.cool_deg_days), 1),
+            ],
+        ));
+        ui.end_row();
+        ui.label(lang.tr(text!("Pluie")));
+        ui.label(units.format(lang, Quantity::Rain, day.rain as f64));
+        ui.end_row();
+        ui.label(lang.tr(text!("Vent moyen")));
+        ui.label(wind(day.avg_wind_speed));
+        ui.end_row();
+        ui.label(lang.tr(text!("Rafale maximale")));
+        match day.high_wind_speed_date {
+            Some(at) => ui.label(lang.trf(
+                text!("{} à {}"),
+                &[&wind(day.high_wind_speed), &at.format(time).unwrap()],
+            )),
+            None => ui.label(wind(day.high_wind_speed)),
         };
-        format!("{} - {month}", date.year())
-    }
+        ui.end_row();
+        ui.label(lang.tr(text!("Direction dominante")));
+        ui.label(day.dom_dir.trim());
+        ui.end_row();
+    });
 }
 
-#[derive(Default, Debug, PartialEq, Clone, Copy)]
-pub enum DisplayReport {
+#[derive(Default, Debug, PartialEq, Clone, Copy, serde::Serialize, serde::Deserialize)]
+pub enum Tab {
     #[default]
     Temperature,
     Rain,
     Wind,
+    WindRose,
+    DegreeDays,
+    Comparison,
+    Summary,
+    Quality,
     Text,
 }
 
+impl Tab {
+    const ALL: [Tab; 9] = [
+        Tab::Temperature,
+        Tab::Rain,
+        Tab::Wind,
+        Tab::WindRose,
+        Tab::DegreeDays,
+        Tab::Comparison,
+        Tab::Summary,
+        Tab::Quality,
+        Tab::Text,
+    ];
+
+    /// How the tab is named in the URL.
+    pub fn slug(self) -> &'static str {
+        match self {
+            Tab::Temperature => "temperatures",
+            Tab::Rain => "pluie",
+            Tab::Wind => "vent",
+            Tab::WindRose => "rose-des-vents",
+            Tab::DegreeDays => "degres-jours",
+            Tab::Comparison => "comparaison",
+            Tab::Summary => "resume",
+            Tab::Quality => "qualite",
+            Tab::Text => "texte",
+        }
+    }
+
+    pub fn from_slug(slug: &str) -> Option<Tab> {
+        Tab::ALL.into_iter().find(|tab| tab.slug() == slug)
+    }
+}
+
+#[derive(Default, Debug, PartialEq, Clone, Copy)]
+pub enum TemperatureMode {
+    #[default]
+    Daily,
+    Anomaly,
+}
+
+#[derive(Default, Debug, PartialEq, Clone, Copy)]
+pub enum RainMode {
+    #[default]
+    Daily,
+    Cumulative,
+    Anomaly,
+}
+
+#[derive(Debug, Clone)]
+pub struct DisplayReport {
+    tab: Tab,
+    /// The x range of the last time plot we displayed, the views that are not
+    /// drawn against the time use it to know which period they should cover.
+    visible: Option<RangeInclusive<f64>>,
+    /// A period the plots against the time must move to next time they're shown.
+    focus: Option<RangeInclusive<f64>>,
+    wind_speed: WindSpeed,
+    temperature_mode: TemperatureMode,
+    rain_mode: RainMode,
+    anomaly_aggregation: Aggregation,
+    show_normals: bool,
+    comparison: Comparison,
+    range_bar: RangeBar,
+    export_visible: bool,
+    /// The result of the last export.
+    export_status: Option<String>,
+    /// Computed the first time it's needed.
+    quality: Option<Quality>,
+    /// Computed from the whole history the first time it's needed.
+    year_to_date: Option<YearToDate>,
+}
+
+impl Default for DisplayReport {
+    fn default() -> Self {
+        Self {
+            tab: Tab::default(),
+            visible: None,
+            focus: None,
+            wind_speed: WindSpeed::default(),
+            temperature_mode: TemperatureMode::default(),
+            rain_mode: RainMode::default(),
+            anomaly_aggregation: Aggregation::Week,
+            show_normals: true,
+            comparison: Comparison::default(),
+            range_bar: RangeBar::default(),
+            export_visible: false,
+            export_status: None,
+            quality: None,
+            year_to_date: None,
+        }
+    }
+}
+
+/// What is restored of a [`DisplayReport`] when the app is reopened.
+#[derive(Default, Debug, Clone, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+pub struct DisplaySession {
+    tab: Tab,
+    visible: Option<RangeInclusive<f64>>,
+}
+
 impl DisplayReport {
-    pub fn ui(&mut self, report: &Report, ui: &mut Ui) {
+    pub fn session(&self) -> DisplaySession {
+        DisplaySession {
+            tab: self.tab,
+            visible: self.visible.clone(),
+        }
+    }
+
+    pub fn restore(&mut self, session: DisplaySession) {
+        self.tab = session.tab;
+        if let Some(range) = session.visible {
+            self.show_range(range);
+        }
+    }
+
+    pub fn tab(&self) -> Tab {
+        self.tab
+    }
+
+    pub fn set_tab(&mut self, tab: Tab) {
+        self.tab = tab;
+    }
+
+    /// The period the user is looking at.
+    pub fn visible(&self) -> Option<&RangeInclusive<f64>> {
+        self.visible.as_ref()
+    }
+
+    /// Moves the plots against the time to `range` the next time they're shown, the
+    /// views that are not drawn against the time follow it right away.
+    pub fn show_range(&mut self, range: RangeInclusive<f64>) {
+        self.focus = Some(range.clone());
+        self.visible = Some(range);
+    }
+
+    pub fn ui(&mut self, report: &Report, normals: &mut Normals, ui: &mut Ui) {
+        let lang = Language::get(ui.ctx());
         ui.horizontal(|ui| {
-            ui.selectable_value(self, Self::Temperature, "Températures");
-            ui.selectable_value(self, Self::Rain, "Pluie");
-            ui.selectable_value(self, Self::Wind, "Vent");
+            ui.selectable_value(
+                &mut self.tab,
+                Tab::Temperature,
+                lang.tr(text!("Températures")),
+            );
+            ui.selectable_value(&mut self.tab, Tab::Rain, lang.tr(text!("Pluie")));
+            ui.selectable_value(&mut self.tab, Tab::Wind, lang.tr(text!("Vent")));
+            ui.selectable_value(
+                &mut self.tab,
+                Tab::WindRose,
+                lang.tr(text!("Rose des vents")),
+            );
+            ui.selectable_value(
+                &mut self.tab,
+                Tab::DegreeDays,
+                lang.tr(text!("Degrés-jours")),
+            );
+            ui.selectable_value(
+                &mut self.tab,
+                Tab::Comparison,
+                lang.tr(text!("Comparaison")),
+            );
+            ui.selectable_value(&mut self.tab, Tab::Summary, lang.tr(text!("Résumé")));
+            ui.selectable_value(&mut self.tab, Tab::Quality, lang.tr(text!("Qualité")));
             if report.original.is_some() {
-                ui.selectable_value(self, Self::Text, "Texte");
+                ui.selectable_value(&mut self.tab, Tab::Text, lang.tr(text!("Texte")));
             }
+            ui.with_layout(Layout::right_to_left(Align::Center), |ui| {
+                self.export_ui(&report.report, ui);
+            });
         });
         ui.separator();
 
-        match self {
-            DisplayReport::Temperature => self.temperature(report, ui),
-            DisplayReport::Rain => self.rain(report, ui),
-            DisplayReport::Wind => self.wind(report, ui),
-            DisplayReport::Text => self.text(report, ui),
+        if self.against_time() {
+            if let Some(range) = self.range_bar.ui(&report.report, self.visible.as_ref(), ui) {
+                self.show_range(range);
+            }
+            ui.separator();
+        }
+
+        match self.tab {
+            Tab::Temperature => self.temperature(report, normals, ui),
+            Tab::Rain => self.rain(report, normals, ui),
+            Tab::Wind => self.wind(report, ui),
+            Tab::WindRose => self.wind_rose(report, ui),
+            Tab::DegreeDays => self.degree_days(report, ui),
+            Tab::Comparison => self.comparison.ui(&report.report, ui),
+            Tab::Summary => Summary::new(&report.report).ui(ui),
+            Tab::Quality => self.quality_ui(&report.report, ui),
+            Tab::Text => self.text(report, ui),
         }
     }
 
-    pub fn temperature(&mut self, report: &Report, ui: &mut Ui) {
-        let report = &report.report;
-        let plot = create_plot_time("Temperature", report, |degree| format!("{degree:.2}°C"))
-            .link_axis(ui.id(), [true, false])
-            .custom_y_axes(vec![AxisHints::new_y().label("Temperature en °C")]);
-        plot.show(ui, |ui| {
-            // gather all data
-            let low_temp: Vec<_> = report
-                .days
-                .iter()
-                .map(|day| {
-                    [
-                        date_to_chart(day.low_temp_date.assume_utc()),
-                        day.low_temp as f64,
-                    ]
+    /// Exports the days of the report as CSV, only those of the visible period if
+    /// the user asks for it.
+    fn export_ui(&mut self, report: &noaa::Report, ui: &mut Ui) {
+        let lang = Language::get(ui.ctx());
+        if ui.button(lang.tr(text!("Exporter"))).clicked() {
+            let visible = self.visible.as_ref().filter(|_| self.export_visible);
+            let range = visible
+                .and_then(|range| {
+                    Some(
+                        date_from_chart(*range.start())?.date()
+                            ..=date_from_chart(*range.end())?.date(),
+                    )
                 })
-                .collect();
-            let mean_temp: Vec<_> = report
-                .days
-                .iter()
-                .map(|day| {
+                .unwrap_or_else(|| report.first_date()..=report.last_date());
+            let format = format_description!("[year]-[month]-[day]");
+            let name = format!(
+                "meteo-{}-{}.csv",
+                range.start().format(format).unwrap(),
+                range.end().format(format).unwrap()
+            );
+            let csv = export::to_csv(report, range, Units::get(ui.ctx()));
+            self.export_status = Some(match export::save(&name, csv, lang) {
+                Ok(status) => status,
+                Err(error) => lang.trf(text!("Échec de l'export : {}"), &[&error]),
+            });
+        }
+        ui.checkbox(
+            &mut self.export_visible,
+            lang.tr(text!("Période visible uniquement")),
+        );
+        if let Some(status) = &self.export_status {
+            ui.label(status);
+        }
+    }
+
+    /// Whether the current view covers the period of the plots against the time.
+    fn against_time(&self) -> bool {
+        match self.tab {
+            Tab::Temperature | Tab::Wind | Tab::WindRose | Tab::DegreeDays => true,
+            Tab::Rain => self.rain_mode != RainMode::Cumulative,
+            Tab::Comparison | Tab::Summary | Tab::Quality | Tab::Text => false,
+        }
+    }
+
+    /// Moves the plots against the time to the period around `date`.
+    pub fn focus(&mut self, date: Date) {
+        let date = date.with_hms(12, 0, 0).unwrap().assume_utc();
+        self.focus = Some(
+            date_to_chart(date - Duration::DAY * 15)..=date_to_chart(date + Duration::DAY * 15),
+        );
+        if !matches!(self.tab, Tab::Rain | Tab::Wind | Tab::DegreeDays) {
+            self.tab = Tab::Temperature;
+        }
+    }
+
+    fn quality(&mut self, report: &noaa::Report) -> &Quality {
+        self.quality.get_or_insert_with(|| Quality::new(report))
+    }
+
+    /// The suspicious values of `sensors`, at the middle of their day.
+    fn flagged_points(
+        &mut self,
+        report: &noaa::Report,
+        sensors: &[Sensor],
+        units: Units,
+    ) -> Vec<[f64; 2]> {
+        let quality = self.quality(report);
+        sensors
+            .iter()
+            .flat_map(|sensor| {
+                quality.flagged(*sensor).map(move |index| {
+                    let date = report.days[index].date.with_hms(12, 0, 0).unwrap();
                     [
-                        date_to_chart(day.date.with_hms(12, 0, 0).unwrap().assume_utc()),
-                        day.mean_temp as f64,
+                        date_to_chart(date.assume_utc()),
+                        units.convert(sensor.quantity(), sensor.value(report, index)),
                     ]
                 })
-                .collect();
-            let high_temp: Vec<_> = report
+            })
+            .collect()
+    }
+
+    /// The list of the suspicious days of the report.
+    fn quality_ui(&mut self, report: &noaa::Report, ui: &mut Ui) {
+        let lang = Language::get(ui.ctx());
+        let mut clicked = None;
+        let issues = self.quality(report).issues();
+        if issues.is_empty() {
+            ui.label(lang.tr(text!("Aucune donnée suspecte dans ce rapport.")));
+            return;
+        }
+        ui.label(lang.trf(text!("{} problème(s) détecté(s)"), &[&issues.len()]));
+        let units = Units::get(ui.ctx());
+        ui.separator();
+        egui::ScrollArea::vertical().show(ui, |ui| {
+            Grid::new("quality")
+                .num_columns(2)
+                .striped(true)
+                .spacing([20.0, 6.0])
+                .show(ui, |ui| {
+                    ui.label(RichText::new(lang.tr(text!("Période"))).strong());
+                    ui.label(RichText::new(lang.tr(text!("Problème"))).strong());
+                    ui.end_row();
+                    for issue in issues {
+                        if ui
+                            .link(issue.period(lang))
+                            .on_hover_text(lang.tr(text!("Voir sur les courbes")))
+                            .clicked()
+                        {
+                            clicked = Some(issue.start);
+                        }
+                        ui.label(issue.problem.describe(lang, units));
+                        ui.end_row();
+                    }
+                });
+        });
+        if let Some(date) = clicked {
+            self.focus(date);
+        }
+    }
+
+    /// Shows a plot against the time. Applies the period requested by [`Self::focus`],
+    /// shades the days missing from the report and remembers which period the user is
+    /// looking at.
+    fn show_time_plot(
+        &mut self,
+        plot: Plot<'_>,
+        report: &noaa::Report,
+        ui: &mut Ui,
+        build: impl FnOnce(&mut PlotUi),
+    ) {
+        let lang = Language::get(ui.ctx());
+        let gaps: Vec<_> = self
+            .quality(report)
+            .issues()
+            .iter()
+            .filter(|issue| issue.problem == Problem::Gap)
+            .map(|issue| {
+                let start = issue.start.midnight().assume_utc();
+                let end = (issue.end + Duration::DAY).midnight().assume_utc();
+                date_to_chart(start)..=date_to_chart(end)
+            })
+            .collect();
+        let focus = self.focus.take();
+        let response = plot.show(ui, |ui| {
+            if let Some(range) = focus {
+                let bounds = ui.plot_bounds();
+                ui.set_plot_bounds(PlotBounds::from_min_max(
+                    [*range.start(), bounds.min()[1]],
+                    [*range.end(), bounds.max()[1]],
+                ));
+                // only the x axis is forced, the temperature should still fit
+                ui.set_auto_bounds(Vec2b::new(false, true));
+            }
+            build(ui)
+        });
+        self.visible = Some(response.transform.bounds().range_x());
+
+        // painted over the plot rather than added to it so it doesn't weigh on the bounds
+        let frame = *response.transform.frame();
+        let painter = ui.painter_at(frame);
+        for gap in gaps {
+            let left = response.transform.position_from_point_x(*gap.start());
+            let right = response.transform.position_from_point_x(*gap.end());
+            let rect = Rect::from_x_y_ranges(left..=right, frame.y_range()).intersect(frame);
+            if !rect.is_positive() {
+                continue;
+            }
+            painter.rect_filled(rect, 0.0, Color32::GRAY.gamma_multiply(0.2));
+            if rect.width() > 120.0 {
+                painter.text(
+                    rect.center_top() + Vec2::new(0.0, 8.0),
+                    Align2::CENTER_TOP,
+                    lang.tr(text!("données manquantes")),
+                    FontId::proportional(12.0),
+                    ui.visuals().weak_text_color(),
+                );
+            }
+        }
+    }
+
+    pub fn temperature(&mut self, report: &Report, normals: &mut Normals, ui: &mut Ui) {
+        let lang = Language::get(ui.ctx());
+        let report = &report.report;
+        ui.horizontal(|ui| {
+            ui.selectable_value(
+                &mut self.temperature_mode,
+                TemperatureMode::Daily,
+                lang.tr(text!("Quotidienne")),
+            );
+            ui.selectable_value(
+                &mut self.temperature_mode,
+                TemperatureMode::Anomaly,
+                lang.tr(text!("Écart à la normale")),
+            );
+            ui.separator();
+            match self.temperature_mode {
+                TemperatureMode::Daily => {
+                    ui.checkbox(&mut self.show_normals, lang.tr(text!("Normales")));
+                    if self.show_normals {
+                        normals.period_ui(ui);
+                    }
+                }
+                TemperatureMode::Anomaly => {
+                    for aggregation in [Aggregation::Day, Aggregation::Week, Aggregation::Month] {
+                        ui.selectable_value(
+                            &mut self.anomaly_aggregation,
+                            aggregation,
+                            lang.tr(aggregation.name()),
+                        );
+                    }
+                    ui.separator();
+                    normals.period_ui(ui);
+                }
+            }
+        });
+        if self.temperature_mode == TemperatureMode::Anomaly {
+            self.temperature_anomaly(report, normals, ui);
+            return;
+        }
+
+        let units = Units::get(ui.ctx());
+        let temperature = move |value: f64| units.convert(Quantity::Temperature, value);
+        let flagged = self.flagged_points(
+            report,
+            &[Sensor::LowTemp, Sensor::MeanTemp, Sensor::HighTemp],
+            units,
+        );
+        let show_normals = self.show_normals;
+        let plot = create_plot_time("Temperature", lang, report, move |degree| {
+            units.format_plotted(lang, Quantity::Temperature, degree)
+        })
+        .link_axis(ui.id(), [true, false])
+        .custom_y_axes(vec![AxisHints::new_y().label(lang.trf(
+            text!("Température en {}"),
+            &[&units.symbol(Quantity::Temperature)],
+        ))]);
+        self.show_time_plot(plot, report, ui, |ui| {
+            // zoomed out the days are grouped, each line keeps its own extreme
+            let bounds = ui.plot_bounds();
+            let lod = aggregation_for_range(bounds.min()[0], bounds.max()[0]);
+
+            // gather all data
+            let low_temp = level_of_detail(
+                report.days.iter().map(|day| {
+                    let x = date_to_chart(day.low_temp_date.assume_utc());
+                    (day.date, [x, temperature(day.low_temp as f64)])
+                }),
+                lod,
+                Reduce::Min,
+            );
+            let mean_temp = level_of_detail(
+                report.days.iter().map(|day| {
+                    let x = date_to_chart(day.date.with_hms(12, 0, 0).unwrap().assume_utc());
+                    (day.date, [x, temperature(day.mean_temp as f64)])
+                }),
+                lod,
+                Reduce::Mean,
+            );
+            let high_temp = level_of_detail(
+                report.days.iter().map(|day| {
+                    let x = date_to_chart(day.high_temp_date.assume_utc());
+                    (day.date, [x, temperature(day.high_temp as f64)])
+                }),
+                lod,
+                Reduce::Max,
+            );
+
+            // display the normals first so they stay behind the data
+            if show_normals {
+                let (mut low, mut mean, mut high) = (Vec::new(), Vec::new(), Vec::new());
+                for day in report.days.iter() {
+                    if let Some(normal) = normals.get(day.date) {
+                        let date = date_to_chart(day.date.with_hms(12, 0, 0).unwrap().assume_utc());
+                        low.push((day.date, [date, temperature(normal.low)]));
+                        mean.push((day.date, [date, temperature(normal.mean)]));
+                        high.push((day.date, [date, temperature(normal.high)]));
+                    }
+                }
+                let low = level_of_detail(low.into_iter(), lod, Reduce::Min);
+                let mean = level_of_detail(mean.into_iter(), lod, Reduce::Mean);
+                let high = level_of_detail(high.into_iter(), lod, Reduce::Max);
+                let lines = [
+                    (lang.tr(text!("normale minimale")), Color32::LIGHT_BLUE, low),
+                    (lang.tr(text!("normale moyenne")), Color32::GREEN, mean),
+                    (lang.tr(text!("normale maximale")), Color32::RED, high),
+                ];
+                for (name, color, segments) in lines {
+                    for points in segments {
+                        ui.line(
+                            Line::new(name, points)
+                                .color(color.gamma_multiply(0.6))
+                                .style(LineStyle::dashed_loose())
+                                .name(name),
+                        );
+                    }
+                }
+            }
+
+            // zoomed out, the range of each period is filled around its mean
+            if lod != Aggregation::Day {
+                band(
+                    ui,
+                    lang.tr(text!("écart minimum–maximum")),
+                    Color32::GRAY,
+                    &low_temp,
+                    &high_temp,
+                );
+            }
+
+            // display all data, one line per segment so the missing days stay empty
+            let lines = [
+                (
+                    lang.tr(text!("temperature minimale")),
+                    Color32::LIGHT_BLUE,
+                    low_temp,
+                ),
+                (
+                    lang.tr(text!("temperature moyenne")),
+                    Color32::GREEN,
+                    mean_temp,
+                ),
+                (
+                    lang.tr(text!("temperature maximale")),
+                    Color32::RED,
+                    high_temp,
+                ),
+            ];
+            for (name, color, segments) in lines {
+                for points in segments {
+                    ui.line(Line::new(name, points).color(color).name(name));
+                }
+            }
+            // the flags would float away from the grouped points
+            if lod == Aggregation::Day {
+                flag(ui, flagged);
+            }
+        });
+    }
+
+    pub fn rain(&mut self, report: &Report, normals: &mut Normals, ui: &mut Ui) {
+        let lang = Language::get(ui.ctx());
+        ui.horizontal(|ui| {
+            ui.selectable_value(
+                &mut self.rain_mode,
+                RainMode::Daily,
+                lang.tr(text!("Quotidienne")),
+            );
+            ui.selectable_value(
+                &mut self.rain_mode,
+                RainMode::Cumulative,
+                lang.tr(text!("Cumul annuel")),
+            );
+            ui.selectable_value(
+                &mut self.rain_mode,
+                RainMode::Anomaly,
+                lang.tr(text!("Écart à la normale")),
+            );
+            if self.rain_mode == RainMode::Anomaly {
+                ui.separator();
+                normals.period_ui(ui);
+            }
+        });
+        match self.rain_mode {
+            RainMode::Daily => (),
+            RainMode::Cumulative => return self.cumulative_rain(report, normals, ui),
+            RainMode::Anomaly => return self.rain_anomaly(&report.report, normals, ui),
+        }
+
+        let report = &report.report;
+        let units = Units::get(ui.ctx());
+        let flagged = self.flagged_points(report, &[Sensor::Rain], units);
+        let plot = create_plot_time("Pluie", lang, report, move |rain| {
+            units.format_plotted(lang, Quantity::Rain, rain)
+        })
+        .link_axis(ui.id(), [true, false])
+        .custom_y_axes(vec![AxisHints::new_y()
+            .label(lang.trf(text!("Pluie en {}"), &[&units.symbol(Quantity::Rain)]))]);
+        self.show_time_plot(plot, report, ui, |ui| {
+            // the bars get wider as the user zooms out
+            let bounds = ui.plot_bounds();
+            let aggregation = aggregation_for_range(bounds.min()[0], bounds.max()[0]);
+            let rain = report
                 .days
                 .iter()
-                .map(|day| {
-                    [
-                        date_to_chart(day.high_temp_date.assume_utc()),
-                        day.high_temp as f64,
-                    ]
+                .map(|day| (day.date, units.convert(Quantity::Rain, day.rain as f64)));
+            let bars: Vec<_> = aggregate(rain, aggregation)
+                .into_iter()
+                .map(|bucket| {
+                    Bar::new(bucket.center(), bucket.sum)
+                        .width(bucket.width() * 0.9)
+                        .name(aggregation.label(bucket.start, lang))
                 })
                 .collect();
 
-            // display all data
-            ui.line(
-                Line::new("temperature minimale", low_temp)
+            ui.bar_chart(
+                BarChart::new(lang.tr(text!("pluie")), bars)
                     .color(Color32::LIGHT_BLUE)
-                    .name("temperature minimale"),
-            );
-            ui.line(
-                Line::new("temperature moyenne", mean_temp)
-                    .color(Color32::GREEN)
-                    .name("temperature moyenne"),
+                    .element_formatter(Box::new(move |bar, _| {
+                        format!(
+                            "{}\n{}",
+                            bar.name,
+                            units.format_plotted(lang, Quantity::Rain, bar.value)
+                        )
+                    }))
+                    .name(lang.tr(text!("pluie"))),
             );
-            ui.line(
-                Line::new("temperature maximale", high_temp)
+            if aggregation == Aggregation::Day {
+                flag(ui, flagged);
+            }
+        });
+    }
+
+    /// The difference between the mean temperature and its normal.
+    pub fn temperature_anomaly(&mut self, report: &noaa::Report, normals: &Normals, ui: &mut Ui) {
+        let lang = Language::get(ui.ctx());
+        let aggregation = self.anomaly_aggregation;
+        let units = Units::get(ui.ctx());
+        let difference = move |degree: f64| {
+            let plotted = units.format_plotted(lang, Quantity::TemperatureDifference, degree);
+            if degree >= 0.0 {
+                format!("+{plotted}")
+            } else {
+                plotted
+            }
+        };
+        let plot = create_plot_time("Anomalie de température", lang, report, difference)
+            .link_axis(ui.id(), [true, false])
+            .custom_y_axes(vec![AxisHints::new_y().label(lang.trf(
+                text!("Écart à la normale en {}"),
+                &[&units.symbol(Quantity::TemperatureDifference)],
+            ))]);
+        self.show_time_plot(plot, report, ui, |ui| {
+            let anomalies = report.days.iter().filter_map(|day| {
+                let normal = normals.get(day.date)?;
+                let anomaly = day.mean_temp as f64 - normal.mean;
+                Some((
+                    day.date,
+                    units.convert(Quantity::TemperatureDifference, anomaly),
+                ))
+            });
+            let (warmer, colder): (Vec<_>, Vec<_>) = aggregate(anomalies, aggregation)
+                .into_iter()
+                .map(|bucket| {
+                    Bar::new(bucket.center(), bucket.mean())
+                        .width(bucket.width() * 0.9)
+                        .name(aggregation.label(bucket.start, lang))
+                })
+                .partition(|bar| bar.value >= 0.0);
+
+            let formatter =
+                move |bar: &Bar, _: &BarChart| format!("{}\n{}", bar.name, difference(bar.value));
+            ui.bar_chart(
+                BarChart::new(lang.tr(text!("plus chaud que la normale")), warmer)
                     .color(Color32::RED)
-                    .name("temperature maximale"),
+                    .element_formatter(Box::new(formatter))
+                    .name(lang.tr(text!("plus chaud que la normale"))),
+            );
+            ui.bar_chart(
+                BarChart::new(lang.tr(text!("plus froid que la normale")), colder)
+                    .color(Color32::LIGHT_BLUE)
+                    .element_formatter(Box::new(formatter))
+                    .name(lang.tr(text!("plus froid que la normale"))),
             );
         });
     }
 
-    pub fn rain(&mut self, report: &Report, ui: &mut Ui) {
-        let report = &report.report;
-        let plot = create_plot_time("Pluie", report, |rain| format!("{rain:.2}mm"))
-            .link_axis(ui.id(), [true, false])
-            .custom_y_axes(vec![AxisHints::new_y().label("Pluie en mm/m²")]);
-        plot.show(ui, |ui| {
-            // gather all data
-            let rain: Vec<_> = report
-                .days
-                .iter()
-                .map(|day| {
-                    [
-                        date_to_chart(day.date.with_hms(12, 0, 0).unwrap().assume_utc()),
-                        day.rain as f64,
-                    ]
+    /// The rain of each month as a percentage of its normal.
+    pub fn rain_anomaly(&mut self, report: &noaa::Report, normals: &Normals, ui: &mut Ui) {
+        let lang = Language::get(ui.ctx());
+        let units = Units::get(ui.ctx());
+        let plot = create_plot_time("Anomalie de pluie", lang, report, move |percent| {
+            format!("{}%", lang.number(percent, 0))
+        })
+        .link_axis(ui.id(), [true, false])
+        .custom_y_axes(vec![
+            AxisHints::new_y().label(lang.tr(text!("Pluie en % de la normale")))
+        ]);
+        self.show_time_plot(plot, report, ui, |ui| {
+            let rain = report.days.iter().map(|day| (day.date, day.rain as f64));
+            let (wetter, drier): (Vec<_>, Vec<_>) = aggregate(rain, Aggregation::Month)
+                .into_iter()
+                .filter_map(|bucket| {
+                    let month = bucket.start.month();
+                    // the ongoing month or a month with missing days is compared to
+                    // the normal of the days we have
+                    let length = month.length(bucket.start.year()) as usize;
+                    let normal = normals.monthly_rain(month)? * bucket.count as f64 / length as f64;
+                    let label = Aggregation::Month.label(bucket.start, lang);
+                    let label = if bucket.count < length {
+                        lang.trf(
+                            text!("{} ({} jours sur {})"),
+                            &[&label, &bucket.count, &length],
+                        )
+                    } else {
+                        label
+                    };
+                    (normal > 0.0).then(|| {
+                        Bar::new(bucket.center(), bucket.sum / normal * 100.0)
+                            .width(bucket.width() * 0.9)
+                            .name(lang.trf(
+                                text!("{} : {} pour une normale de {}"),
+                                &[
+                                    &label,
+                                    &units.format(lang, Quantity::Rain, bucket.sum),
+                                    &units.format(lang, Quantity::Rain, normal),
+                                ],
+                            ))
+                    })
                 })
-                .collect();
+                .partition(|bar| bar.value >= 100.0);
 
-            // display all data
-            ui.line(
-                Line::new("pluie", rain)
+            let formatter = move |bar: &Bar, _: &BarChart| {
+                format!("{}\n{}%", bar.name, lang.number(bar.value, 0))
+            };
+            ui.hline(HLine::new(lang.tr(text!("normale")), 100.0).color(Color32::GRAY));
+            ui.bar_chart(
+                BarChart::new(lang.tr(text!("plus humide que la normale")), wetter)
                     .color(Color32::LIGHT_BLUE)
-                    .name("pluie"),
+                    .element_formatter(Box::new(formatter))
+                    .name(lang.tr(text!("plus humide que la normale"))),
+            );
+            ui.bar_chart(
+                BarChart::new(lang.tr(text!("plus sec que la normale")), drier)
+                    .color(Color32::from_rgb(200, 140, 60))
+                    .element_formatter(Box::new(formatter))
+                    .name(lang.tr(text!("plus sec que la normale"))),
             );
         });
     }
 
+    pub fn cumulative_rain(&mut self, report: &Report, normals: &Normals, ui: &mut Ui) {
+        let lang = Language::get(ui.ctx());
+        let history = normals.history();
+        let year_to_date = self
+            .year_to_date
+            .get_or_insert_with(|| YearToDate::new(history));
+        // the year we're looking at and the ongoing year stand out
+        let highlighted = [report.report.last_date().year(), history.last_date().year()];
+
+        let units = Units::get(ui.ctx());
+        let rain = move |points: Vec<[f64; 2]>| -> Vec<[f64; 2]> {
+            points
+                .into_iter()
+                .map(|[x, y]| [x, units.convert(Quantity::Rain, y)])
+                .collect()
+        };
+        let plot = create_plot_day_of_year("Cumul de pluie", lang, move |total| {
+            units.format_plotted(lang, Quantity::Rain, total)
+        })
+        .custom_y_axes(vec![AxisHints::new_y().label(lang.trf(
+            text!("Pluie depuis le 1er janvier en {}"),
+            &[&units.symbol(Quantity::Rain)],
+        ))]);
+        plot.show(ui, |ui| {
+            let [min, mean, max] = year_to_date.envelope();
+            let envelope = [
+                (lang.tr(text!("minimum historique")), rain(min)),
+                (lang.tr(text!("moyenne historique")), rain(mean)),
+                (lang.tr(text!("maximum historique")), rain(max)),
+            ];
+            for (name, points) in envelope {
+                ui.line(
+                    Line::new(name, points)
+                        .color(Color32::GRAY)
+                        .style(LineStyle::dashed_loose())
+                        .name(name),
+                );
+            }
+
+            for (year, totals) in year_to_date.years.iter() {
+                let points: Vec<_> = totals
+                    .totals
+                    .iter()
+                    .enumerate()
+                    .filter_map(|(slot, total)| {
+                        Some([slot as f64, units.convert(Quantity::Rain, (*total)?)])
+                    })
+                    .collect();
+                let name = year.to_string();
+                let line = Line::new(&name, points).name(&name);
+                if highlighted.contains(year) {
+                    ui.line(line.width(2.5));
+                } else {
+                    ui.line(line.color(Color32::GRAY.gamma_multiply(0.4)));
+                }
+            }
+        });
+    }
+
     pub fn wind(&mut self, report: &Report, ui: &mut Ui) {
+        let lang = Language::get(ui.ctx());
         let report = &report.report;
-        let plot = create_plot_time("Vent", report, |wind| format!("{wind:.2}km/h"))
+        let units = Units::get(ui.ctx());
+        let wind = move |speed: f32| units.convert(Quantity::Wind, speed as f64);
+        let flagged = self.flagged_points(report, &[Sensor::AvgWind, Sensor::HighWind], units);
+        let plot = create_plot_time("Vent", lang, report, move |speed| {
+            units.format_plotted(lang, Quantity::Wind, speed)
+        })
+        .link_axis(ui.id(), [true, false])
+        .custom_y_axes(vec![AxisHints::new_y()
+            .label(lang.trf(text!("Vent en {}"), &[&units.symbol(Quantity::Wind)]))]);
+        self.show_time_plot(plot, report, ui, |ui| {
+            let bounds = ui.plot_bounds();
+            let lod = aggregation_for_range(bounds.min()[0], bounds.max()[0]);
+
+            let mean_wind = level_of_detail(
+                report.days.iter().map(|day| {
+                    let x = date_to_chart(day.date.with_hms(12, 0, 0).unwrap().assume_utc());
+                    (day.date, [x, wind(day.avg_wind_speed)])
+                }),
+                lod,
+                Reduce::Mean,
+            );
+            let high_wind = level_of_detail(
+                report.days.iter().map(|day| {
+                    let date = day
+                        .high_wind_speed_date
+                        .unwrap_or_else(|| day.date.with_hms(12, 0, 0).unwrap());
+                    (
+                        day.date,
+                        [date_to_chart(date.assume_utc()), wind(day.high_wind_speed)],
+                    )
+                }),
+                lod,
+                Reduce::Max,
+            );
+
+            // display all data, one line per segment so the missing days stay empty
+            let lines = [
+                (lang.tr(text!("vent moyen")), Color32::GREEN, mean_wind),
+                (lang.tr(text!("vent maximal")), Color32::RED, high_wind),
+            ];
+            for (name, color, segments) in lines {
+                for points in segments {
+                    ui.line(Line::new(name, points).color(color).name(name));
+                }
+            }
+            if lod == Aggregation::Day {
+                flag(ui, flagged);
+            }
+        });
+    }
+
+    pub fn wind_rose(&mut self, report: &Report, ui: &mut Ui) {
+        let lang = Language::get(ui.ctx());
+        let report = &report.report;
+        let (start, end) = self
+            .visible
+            .as_ref()
+            .and_then(|range| {
+                Some((
+                    date_from_chart(*range.start())?,
+                    date_from_chart(*range.end())?,
+                ))
+            })
+            .unwrap_or_else(|| default_range(report));
+
+        ui.horizontal(|ui| {
+            ui.label(lang.trf(
+                text!("Du {} au {}"),
+                &[&lang.date(start.date()), &lang.date(end.date())],
+            ));
+            ui.separator();
+            ui.selectable_value(
+                &mut self.wind_speed,
+                WindSpeed::High,
+                lang.tr(text!("Rafales")),
+            );
+            ui.selectable_value(
+                &mut self.wind_speed,
+                WindSpeed::Average,
+                lang.tr(text!("Vent moyen")),
+            );
+        });
+        ui.separator();
+
+        WindRose::new(report, start.date()..=end.date(), self.wind_speed).ui(ui);
+    }
+
+    pub fn degree_days(&mut self, report: &Report, ui: &mut Ui) {
+        let lang = Language::get(ui.ctx());
+        let report = &report.report;
+        // Half of the space for the daily values, the other half for the seasonal totals
+        let height = ui.available_height() / 2.0;
+
+        let units = Units::get(ui.ctx());
+        let degree_days =
+            move |value: f32| units.convert(Quantity::TemperatureDifference, value as f64);
+        let unit = format!("{}.j", units.symbol(Quantity::TemperatureDifference));
+        let formatter = {
+            let unit = unit.clone();
+            move |dd: f64| format!("{}{unit}", lang.number(dd, 1))
+        };
+
+        let plot = create_plot_time("Degrés-jours", lang, report, formatter.clone())
+            .height(height)
             .link_axis(ui.id(), [true, false])
-            .custom_y_axes(vec![AxisHints::new_y().label("Vent en km/h")]);
-        plot.show(ui, |ui| {
-            let mean_wind: Vec<_> = report
-                .days
-                .iter()
-                .map(|day| {
-                    [
-                        date_to_chart(day.date.with_hms(12, 0, 0).unwrap().assume_utc()),
-                        day.avg_wind_speed as f64,
-                    ]
-                })
-                .collect();
-            let high_wind: Vec<_> = report
-                .days
-                .iter()
-                .map(|day| {
-                    [
-                        date_to_chart(
-                            day.high_wind_speed_date
-                                .unwrap_or_else(|| day.date.with_hms(12, 0, 0).unwrap())
-                                .assume_utc(),
-                        ),
-                        day.high_wind_speed as f64,
-                    ]
-                })
-                .collect();
+            .custom_y_axes(vec![
+                AxisHints::new_y().label(lang.trf(text!("Degrés-jours en {}"), &[&unit]))
+            ]);
+        self.show_time_plot(plot, report, ui, |ui| {
+            let bounds = ui.plot_bounds();
+            let lod = aggregation_for_range(bounds.min()[0], bounds.max()[0]);
+            let noon = |date: Date| date_to_chart(date.with_hms(12, 0, 0).unwrap().assume_utc());
 
-            // display all data
-            ui.line(
-                Line::new("vent moyen", mean_wind)
-                    .color(Color32::GREEN)
-                    .name("vent moyen"),
+            // gather all data
+            let heat = level_of_detail(
+                report
+                    .days
+                    .iter()
+                    .map(|day| (day.date, [noon(day.date), degree_days(day.heat_deg_days)])),
+                lod,
+                Reduce::Mean,
             );
-            ui.line(
-                Line::new("vent maximal", high_wind)
-                    .color(Color32::RED)
-                    .name("vent maximal"),
+            let cool = level_of_detail(
+                report
+                    .days
+                    .iter()
+                    .map(|day| (day.date, [noon(day.date), degree_days(day.cool_deg_days)])),
+                lod,
+                Reduce::Mean,
             );
+
+            // display all data, one line per segment so the missing days stay empty
+            let lines = [
+                (lang.tr(text!("chauffage")), Color32::RED, heat),
+                (lang.tr(text!("climatisation")), Color32::LIGHT_BLUE, cool),
+            ];
+            for (name, color, segments) in lines {
+                for points in segments {
+                    ui.line(Line::new(name, points).color(color).name(name));
+                }
+            }
+        });
+
+        let plot = create_plot_time("Degrés-jours cumulés", lang, report, formatter)
+            .height(height)
+            .link_axis(ui.id(), [true, false])
+            .custom_y_axes(vec![
+                AxisHints::new_y().label(lang.trf(text!("Cumul de la saison en {}"), &[&unit]))
+            ]);
+        self.show_time_plot(plot, report, ui, |ui| {
+            // The heating season runs from July to June so a whole winter is in the same
+            // season, while the cooling season follows the calendar year.
+            let heat = seasonal_totals(
+                report
+                    .days
+                    .iter()
+                    .map(|day| (day.date, degree_days(day.heat_deg_days))),
+                |date| {
+                    if date.month() as u8 >= Month::July as u8 {
+                        date.year()
+                    } else {
+                        date.year() - 1
+                    }
+                },
+            );
+            let cool = seasonal_totals(
+                report
+                    .days
+                    .iter()
+                    .map(|day| (day.date, degree_days(day.cool_deg_days))),
+                |date| date.year(),
+            );
+
+            // one line per segment so we don't draw the reset to zero nor across the
+            // missing days
+            for segment in heat {
+                ui.line(
+                    Line::new(lang.tr(text!("cumul chauffage")), segment)
+                        .color(Color32::RED)
+                        .name(lang.tr(text!("cumul chauffage"))),
+                );
+            }
+            for segment in cool {
+                ui.line(
+                    Line::new(lang.tr(text!("cumul climatisation")), segment)
+                        .color(Color32::LIGHT_BLUE)
+                        .name(lang.tr(text!("cumul climatisation"))),
+                );
+            }
         });
     }
 
     pub fn text(&mut self, report: &Report, ui: &mut Ui) {
-        if let Some(ref original) = report.original {
+        let lang = Language::get(ui.ctx());
+        match &report.original {
+            Some(url) => original_ui(url, ui),
+            None => {
+                ui.label(lang.tr(text!("Le rapport a été généré, il n'y a pas d'original")));
+            }
+        }
+    }
+}
+
+/// The original text is only downloaded once the Text tab is opened.
+#[cfg(all(feature = "fetch", target_arch = "wasm32"))]
+fn original_ui(url: &str, ui: &mut Ui) {
+    let lang = Language::get(ui.ctx());
+    match crate::fetch::original(ui.ctx(), url) {
+        None => {
+            ui.spinner();
+        }
+        Some(Ok(original)) => {
             ui.label(RichText::new(original).monospace());
-        } else {
-            ui.label("The report was generated and there is no original");
         }
+        Some(Err(error)) => {
+            ui.colored_label(
+                ui.visuals().error_fg_color,
+                lang.trf(
+                    text!("Le texte du rapport n'a pas pu être téléchargé : {}"),
+                    &[&error.tr(lang)],
+                ),
+            );
+        }
+    }
+}
+
+/// Only the web build has somewhere to download the texts from.
+#[cfg(not(all(feature = "fetch", target_arch = "wasm32")))]
+fn original_ui(_url: &str, _ui: &mut Ui) {}
+
+/// Returns the running total of the values for each season, a new season starts every
+/// time `season` returns a different key. A season is split in several segments where
+/// days are missing, the total goes on after the gap.
+fn seasonal_totals(
+    days: impl Iterator<Item = (Date, f64)>,
+    season: impl Fn(Date) -> i32,
+) -> Vec<Vec<[f64; 2]>> {
+    let mut segments: Vec<Vec<[f64; 2]>> = Vec::new();
+    let mut current = None;
+    let mut previous: Option<Date> = None;
+    let mut total = 0.0;
+
+    let mut days: Vec<_> = days.collect();
+    days.sort_by_key(|(date, _)| *date);
+    for (date, value) in days {
+        let key = season(date);
+        if current != Some(key) {
+            current = Some(key);
+            total = 0.0;
+            segments.push(Vec::new());
+        } else if previous.and_then(Date::next_day) != Some(date) {
+            segments.push(Vec::new());
+        }
+        previous = Some(date);
+        total += value;
+        // we just pushed a segment if there was none
+        segments.last_mut().unwrap().push([
+            date_to_chart(date.with_hms(12, 0, 0).unwrap().assume_utc()),
+            total,
+        ]);
+    }
+
+    segments
+}
+
+#[cfg(test)]
+mod tests {
+    use time::macros::date;
+
+    use super::*;
+
+    #[test]
+    fn seasonal_totals_break_over_missing_days() {
+        let days = [
+            (date!(2020 - 12 - 30), 1.0),
+            (date!(2020 - 12 - 31), 1.0),
+            // a new season
+            (date!(2021 - 01 - 01), 1.0),
+            (date!(2021 - 01 - 02), 1.0),
+            // a gap in the same season
+            (date!(2021 - 01 - 05), 1.0),
+        ];
+        let totals: Vec<Vec<f64>> = seasonal_totals(days.into_iter(), |date| date.year())
+            .into_iter()
+            .map(|segment| segment.into_iter().map(|[_, total]| total).collect())
+            .collect();
+        assert_eq!(totals, vec![vec![1.0, 2.0], vec![1.0, 2.0], vec![3.0]]);
     }
 }
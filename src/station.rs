@@ -0,0 +1,356 @@
+use std::collections::BTreeMap;
+
+use egui::{Color32, ComboBox, Context};
+use egui_plot::{AxisHints, Line};
+
+use crate::{
+    aggregate::{level_of_detail, Aggregation, Reduce},
+    dataset::{self, Dataset, Failure},
+    date_to_chart,
+    i18n::{text, Language, Message, Text},
+    noaa,
+    noaa::StationInfo,
+    plot::{aggregation_for_range, band, create_plot_time},
+    report::Report,
+    units::{Quantity, Units},
+};
+
+/// A report by file name, or why it couldn't be loaded.
+pub type ReportFile = (String, Result<Report, Message>);
+
+/// A weather station whose reports are shipped with the app.
+#[derive(Debug)]
+pub struct Source {
+    /// How the station is named in the URL and in the saved session.
+    pub id: &'static str,
+    /// The embedded reports already parsed by `build.rs`, see [`dataset`].
+    dataset: &'static [u8],
+    /// Where the web build downloads the reports from, relative to the app. It
+    /// holds the dataset written by `prepare-data` and a `raw` directory like
+    /// `assets/reports`.
+    #[cfg(all(feature = "fetch", target_arch = "wasm32"))]
+    pub remote: &'static str,
+}
+
+/// Every station the app knows about, the first one is shown by default.
+///
+/// It's generated by `build.rs` from its `STATIONS`, adding a station means
+/// downloading its reports with `prepare-data` in their own directory and adding it
+/// there.
+pub static SOURCES: &[Source] = &include!(concat!(env!("OUT_DIR"), "/sources.rs"));
+
+impl Source {
+    /// Where the web build downloads the text of a report from, the texts aren't
+    /// embedded.
+    #[cfg(all(feature = "fetch", target_arch = "wasm32"))]
+    pub fn original(&self, name: &str) -> Option<String> {
+        Some(crate::fetch::report_url(self.remote, name))
+    }
+
+    /// The other builds have nowhere to get the texts from.
+    #[cfg(not(all(feature = "fetch", target_arch = "wasm32")))]
+    pub fn original(&self, _name: &str) -> Option<String> {
+        None
+    }
+
+    /// The reports embedded in the binary by file name, and what their header says
+    /// about the station.
+    pub fn embedded(&self) -> (StationInfo, Vec<ReportFile>) {
+        self.load(dataset::decode(self.dataset).expect("the embedded dataset is cut short"))
+    }
+
+    /// The reports of a downloaded dataset, a report that couldn't be prepared on the
+    /// server keeps its embedded version and the embedded ones it doesn't have stay.
+    #[cfg(all(feature = "fetch", target_arch = "wasm32"))]
+    pub fn downloaded(&self, dataset: Dataset) -> (StationInfo, Vec<ReportFile>) {
+        let (embedded_info, embedded) = self.embedded();
+        let (info, downloaded) = self.load(dataset);
+        let info = if info == StationInfo::default() {
+            embedded_info
+        } else {
+            info
+        };
+        (info, fallback(embedded, downloaded))
+    }
+
+    fn load(&self, dataset: Dataset) -> (StationInfo, Vec<ReportFile>) {
+        let files = dataset
+            .files
+            .into_iter()
+            .map(|(name, report)| {
+                let report = match report {
+                    Ok(report) => Ok(Report {
+                        original: self.original(&name),
+                        report,
+                    }),
+                    Err(Failure::NotUtf8) => {
+                        Err(Message::new(text!("Le fichier n'est pas en UTF-8"), &[]))
+                    }
+                    Err(Failure::Unreadable(error)) => {
+                        Err(Message::new(text!("Rapport illisible : {}"), &[&error]))
+                    }
+                };
+                (name, report)
+            })
+            .collect();
+        (dataset.station, files)
+    }
+}
+
+/// Replaces the files of `embedded` by the ones of `downloaded` with the same name,
+/// unless they couldn't be read. The files only one of them has are kept.
+#[cfg_attr(not(all(feature = "fetch", target_arch = "wasm32")), allow(dead_code))]
+fn fallback(embedded: Vec<ReportFile>, downloaded: Vec<ReportFile>) -> Vec<ReportFile> {
+    let mut files: BTreeMap<String, Result<Report, Message>> = embedded.into_iter().collect();
+    for (name, report) in downloaded {
+        match files.get(&name) {
+            Some(Ok(_)) if report.is_err() => {
+                log::warn!("Using the embedded {name}, the downloaded one is broken");
+            }
+            _ => {
+                files.insert(name, report);
+            }
+        }
+    }
+    files.into_iter().collect()
+}
+
+impl StationInfo {
+    /// How the station is named to the user.
+    pub fn title(&self, source: &Source) -> String {
+        match (&self.city, &self.name) {
+            (Some(city), Some(name)) => format!("{city} ({name})"),
+            (Some(city), None) => city.clone(),
+            (None, Some(name)) => name.clone(),
+            (None, None) => source.id.to_string(),
+        }
+    }
+}
+
+#[derive(Default, Debug, PartialEq, Clone, Copy)]
+pub enum Metric {
+    #[default]
+    MeanTemp,
+    HighTemp,
+    LowTemp,
+    Rain,
+    HighWind,
+}
+
+impl Metric {
+    const ALL: [Metric; 5] = [
+        Metric::MeanTemp,
+        Metric::HighTemp,
+        Metric::LowTemp,
+        Metric::Rain,
+        Metric::HighWind,
+    ];
+
+    fn name(self) -> Text {
+        match self {
+            Metric::MeanTemp => text!("Température moyenne"),
+            Metric::HighTemp => text!("Température maximale"),
+            Metric::LowTemp => text!("Température minimale"),
+            Metric::Rain => text!("Pluie"),
+            Metric::HighWind => text!("Rafales"),
+        }
+    }
+
+    fn quantity(self) -> Quantity {
+        match self {
+            Metric::MeanTemp | Metric::HighTemp | Metric::LowTemp => Quantity::Temperature,
+            Metric::Rain => Quantity::Rain,
+            Metric::HighWind => Quantity::Wind,
+        }
+    }
+
+    fn value(self, report: &noaa::Report, index: usize) -> f64 {
+        let day = &report.days[index];
+        match self {
+            Metric::MeanTemp => day.mean_temp as f64,
+            Metric::HighTemp => day.high_temp as f64,
+            Metric::LowTemp => day.low_temp as f64,
+            Metric::Rain => day.rain as f64,
+            Metric::HighWind => day.high_wind_speed as f64,
+        }
+    }
+}
+
+/// Draws the same metric of two stations on the same plot.
+#[derive(Default, Debug, Clone)]
+pub struct StationComparison {
+    metric: Metric,
+    /// The station compared to the selected one, `None` picks the first other one.
+    other: Option<usize>,
+}
+
+impl StationComparison {
+    /// `stations` holds the name and history of every station that could be loaded,
+    /// `selected` is the one picked in the top bar.
+    pub fn ui(&mut self, stations: &[(String, &noaa::Report)], selected: usize, ctx: &Context) {
+        egui::CentralPanel::default().show(ctx, |ui| {
+            let lang = Language::get(ctx);
+            let Some((name, report)) = stations.get(selected) else {
+                ui.label(lang.tr(text!("Aucune donnée à afficher")));
+                return;
+            };
+            let others: Vec<usize> = (0..stations.len()).filter(|i| *i != selected).collect();
+            let Some(first) = others.first() else {
+                ui.label(lang.tr(text!(
+                    "Une seule station est disponible, il n'y a rien à comparer."
+                )));
+                return;
+            };
+            let other = self.other.filter(|other| others.contains(other));
+            let other = *self.other.insert(other.unwrap_or(*first));
+
+            ui.horizontal(|ui| {
+                for metric in Metric::ALL {
+                    ui.selectable_value(&mut self.metric, metric, lang.tr(metric.name()));
+                }
+                ui.separator();
+                ui.label(lang.trf(text!("{} comparée à"), &[name]));
+                ComboBox::from_id_salt("compared station")
+                    .selected_text(&stations[other].0)
+                    .show_ui(ui, |ui| {
+                        for i in others.iter() {
+                            ui.selectable_value(&mut self.other, Some(*i), &stations[*i].0);
+                        }
+                    });
+            });
+            ui.separator();
+
+            let metric = self.metric;
+            let units = Units::get(ui.ctx());
+            let quantity = metric.quantity();
+            let plot = create_plot_time("Comparaison des stations", lang, report, move |value| {
+                units.format_plotted(lang, quantity, value)
+            })
+            .custom_y_axes(vec![AxisHints::new_y().label(lang.trf(
+                text!("{} en {}"),
+                &[&lang.tr(metric.name()), &units.symbol(quantity)],
+            ))]);
+            let shown = [
+                (selected, Color32::LIGHT_BLUE),
+                (other, Color32::from_rgb(230, 140, 30)),
+            ];
+            plot.show(ui, |ui| {
+                let bounds = ui.plot_bounds();
+                let lod = aggregation_for_range(bounds.min()[0], bounds.max()[0]);
+                for (station, color) in shown {
+                    let (name, report) = &stations[station];
+                    let points = || {
+                        report.days.iter().enumerate().map(|(index, day)| {
+                            let x =
+                                date_to_chart(day.date.with_hms(12, 0, 0).unwrap().assume_utc());
+                            let y = units.convert(quantity, metric.value(report, index));
+                            (day.date, [x, y])
+                        })
+                    };
+                    // zoomed out, each period is its mean within its lowest and highest day
+                    if lod != Aggregation::Day {
+                        band(
+                            ui,
+                            name,
+                            color,
+                            &level_of_detail(points(), lod, Reduce::Min),
+                            &level_of_detail(points(), lod, Reduce::Max),
+                        );
+                    }
+                    let segments = level_of_detail(points(), lod, Reduce::Mean);
+                    // one line per segment so the missing days stay empty
+                    for points in segments {
+                        ui.line(Line::new(name, points).color(color).name(name));
+                    }
+                }
+            });
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use time::Month;
+
+    use super::*;
+
+    #[test]
+    fn title() {
+        let mut info = StationInfo {
+            name: Some(String::from("maxou")),
+            city: Some(String::from("LE VIGAN")),
+            ..StationInfo::default()
+        };
+        assert_eq!(info.title(&SOURCES[0]), "LE VIGAN (maxou)");
+        info.name = None;
+        assert_eq!(info.title(&SOURCES[0]), "LE VIGAN");
+        info.city = None;
+        assert_eq!(info.title(&SOURCES[0]), "le-vigan");
+    }
+
+    /// Draws the comparison of two made up stations, one of them half as long.
+    #[test]
+    fn compare_two_stations() {
+        let le_vigan = noaa::test::month(2020, Month::September);
+        let mut other = noaa::test::month(2020, Month::September);
+        other.days.truncate(15);
+        let stations = [
+            (String::from("LE VIGAN"), &le_vigan),
+            (String::from("AILLEURS"), &other),
+        ];
+        let ctx = Context::default();
+        let mut comparison = StationComparison::default();
+        for metric in Metric::ALL {
+            comparison.metric = metric;
+            let _ = ctx.run(egui::RawInput::default(), |ctx| {
+                comparison.ui(&stations, 0, ctx);
+            });
+            assert_eq!(comparison.other, Some(1));
+        }
+        // the station compared to can't be the selected one
+        let _ = ctx.run(egui::RawInput::default(), |ctx| {
+            comparison.ui(&stations, 1, ctx);
+        });
+        assert_eq!(comparison.other, Some(0));
+    }
+
+    #[test]
+    fn fallback_file_by_file() {
+        let report = |month| {
+            Ok(Report {
+                original: None,
+                report: noaa::test::month(2020, month),
+            })
+        };
+        let broken = || Err(Message::from(String::from("broken")));
+        let embedded = vec![
+            (String::from("08-2020"), report(Month::August)),
+            (String::from("09-2020"), report(Month::September)),
+            (String::from("10-2020"), broken()),
+        ];
+        let downloaded = vec![
+            (String::from("09-2020"), broken()),
+            (String::from("10-2020"), report(Month::October)),
+            (String::from("11-2020"), report(Month::November)),
+        ];
+        let files = fallback(embedded, downloaded);
+        let months: Vec<_> = files
+            .iter()
+            .map(|(name, report)| {
+                let month = report
+                    .as_ref()
+                    .map(|report| report.report.metadata.date.month());
+                (name.as_str(), month.ok())
+            })
+            .collect();
+        assert_eq!(
+            months,
+            [
+                ("08-2020", Some(Month::August)),
+                ("09-2020", Some(Month::September)),
+                ("10-2020", Some(Month::October)),
+                ("11-2020", Some(Month::November)),
+            ]
+        );
+    }
+}
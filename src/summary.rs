@@ -0,0 +1,280 @@
+use std::collections::BTreeMap;
+
+use egui::{Grid, RichText, Ui};
+use time::{Date, PrimitiveDateTime};
+
+use crate::{
+    i18n::{text, Language},
+    noaa,
+    units::{Quantity, Units},
+};
+
+/// The rain thresholds, in mm, used to count the rainy days in the NOAA footer.
+const RAIN_THRESHOLDS: [f64; 3] = [0.2, 2.0, 20.0];
+
+/// The figures NOAA prints at the bottom of each report, computed from the days
+/// so it works the same for a merged report.
+pub struct Summary {
+    days: usize,
+
+    mean_temp: f64,
+    high_temp: Option<(f64, PrimitiveDateTime)>,
+    low_temp: Option<(f64, PrimitiveDateTime)>,
+    heat_deg_days: f64,
+    cool_deg_days: f64,
+    /// Max >= 32.0
+    hot_days: usize,
+    /// Max <= 0.0
+    ice_days: usize,
+    /// Min <= 0.0
+    frost_days: usize,
+    /// Min <= -18.0
+    severe_frost_days: usize,
+
+    total_rain: f64,
+    max_rain: Option<(f64, Date)>,
+    rain_days: [usize; RAIN_THRESHOLDS.len()],
+
+    mean_wind: f64,
+    high_wind: Option<(f64, PrimitiveDateTime)>,
+    dominant_direction: Option<String>,
+}
+
+impl Summary {
+    pub fn new(report: &noaa::Report) -> Self {
+        let mut summary = Self {
+            days: 0,
+            mean_temp: 0.0,
+            high_temp: None,
+            low_temp: None,
+            heat_deg_days: 0.0,
+            cool_deg_days: 0.0,
+            hot_days: 0,
+            ice_days: 0,
+            frost_days: 0,
+            severe_frost_days: 0,
+            total_rain: 0.0,
+            max_rain: None,
+            rain_days: [0; RAIN_THRESHOLDS.len()],
+            mean_wind: 0.0,
+            high_wind: None,
+            dominant_direction: None,
+        };
+        let mut directions: BTreeMap<String, usize> = BTreeMap::new();
+
+        for day in report.days.iter() {
+            summary.days += 1;
+
+            let high_temp = day.high_temp as f64;
+            let low_temp = day.low_temp as f64;
+            summary.mean_temp += day.mean_temp as f64;
+            keep_extreme(
+                &mut summary.high_temp,
+                high_temp,
+                day.high_temp_date,
+                |a, b| a > b,
+            );
+            keep_extreme(
+                &mut summary.low_temp,
+                low_temp,
+                day.low_temp_date,
+                |a, b| a < b,
+            );
+            summary.heat_deg_days += day.heat_deg_days as f64;
+            summary.cool_deg_days += day.cool_deg_days as f64;
+            summary.hot_days += (high_temp >= 32.0) as usize;
+            summary.ice_days += (high_temp <= 0.0) as usize;
+            summary.frost_days += (low_temp <= 0.0) as usize;
+            summary.severe_frost_days += (low_temp <= -18.0) as usize;
+
+            let rain = day.rain as f64;
+            summary.total_rain += rain;
+            keep_extreme(&mut summary.max_rain, rain, day.date, |a, b| a > b);
+            for (count, threshold) in summary.rain_days.iter_mut().zip(RAIN_THRESHOLDS) {
+                *count += (rain > threshold) as usize;
+            }
+
+            summary.mean_wind += day.avg_wind_speed as f64;
+            let high_wind_date = day
+                .high_wind_speed_date
+                .unwrap_or_else(|| day.date.with_hms(12, 0, 0).unwrap());
+            keep_extreme(
+                &mut summary.high_wind,
+                day.high_wind_speed as f64,
+                high_wind_date,
+                |a, b| a > b,
+            );
+            let direction = day.dom_dir.trim();
+            // `---` means there was no dominant direction this day
+            if direction != "---" {
+                *directions.entry(direction.to_string()).or_default() += 1;
+            }
+        }
+
+        if summary.days > 0 {
+            summary.mean_temp /= summary.days as f64;
+            summary.mean_wind /= summary.days as f64;
+        }
+        summary.dominant_direction = directions
+            .into_iter()
+            .max_by_key(|(_, count)| *count)
+            .map(|(direction, _)| direction);
+
+        summary
+    }
+
+    pub fn ui(&self, ui: &mut Ui) {
+        let lang = Language::get(ui.ctx());
+        if self.days == 0 {
+            ui.label(lang.tr(text!("Aucune donnée dans ce rapport")));
+            return;
+        }
+
+        let units = Units::get(ui.ctx());
+        let temperature = |value: f64| units.format(lang, Quantity::Temperature, value);
+        let threshold = |value: f64| units.format_round(lang, Quantity::Temperature, value);
+
+        egui::ScrollArea::vertical().show(ui, |ui| {
+            ui.label(lang.trf(text!("{} jours de relevés"), &[&self.days]));
+            ui.horizontal_wrapped(|ui| {
+                card(ui, lang.tr(text!("Températures")), |ui| {
+                    row(ui, lang.tr(text!("Moyenne")), temperature(self.mean_temp));
+                    if let Some((temp, at)) = self.high_temp {
+                        row(
+                            ui,
+                            lang.tr(text!("Plus haute")),
+                            lang.trf(
+                                text!("{} le {}"),
+                                &[&temperature(temp), &lang.date_time(at)],
+                            ),
+                        );
+                    }
+                    if let Some((temp, at)) = self.low_temp {
+                        row(
+                            ui,
+                            lang.tr(text!("Plus basse")),
+                            lang.trf(
+                                text!("{} le {}"),
+                                &[&temperature(temp), &lang.date_time(at)],
+                            ),
+                        );
+                    }
+                    let days = [
+                        ("Max ≥", 32.0, self.hot_days),
+                        ("Max ≤", 0.0, self.ice_days),
+                        ("Min ≤", 0.0, self.frost_days),
+                        ("Min ≤", -18.0, self.severe_frost_days),
+                    ];
+                    for (label, value, count) in days {
+                        row(
+                            ui,
+                            &format!("{label} {}", threshold(value)),
+                            lang.trf(text!("{} jours"), &[&count]),
+                        );
+                    }
+                    let degree_days =
+                        |value: f64| units.convert(Quantity::TemperatureDifference, value);
+                    row(
+                        ui,
+                        lang.tr(text!("Degrés-jours de chauffage")),
+                        lang.number(degree_days(self.heat_deg_days), 1),
+                    );
+                    row(
+                        ui,
+                        lang.tr(text!("Degrés-jours de climatisation")),
+                        lang.number(degree_days(self.cool_deg_days), 1),
+                    );
+                });
+
+                card(ui, lang.tr(text!("Pluie")), |ui| {
+                    row(
+                        ui,
+                        lang.tr(text!("Total")),
+                        units.format(lang, Quantity::Rain, self.total_rain),
+                    );
+                    if let Some((rain, at)) = self.max_rain {
+                        row(
+                            ui,
+                            lang.tr(text!("Maximum")),
+                            lang.trf(
+                                text!("{} le {}"),
+                                &[&units.format(lang, Quantity::Rain, rain), &lang.date(at)],
+                            ),
+                        );
+                    }
+                    for (count, threshold) in self.rain_days.iter().zip(RAIN_THRESHOLDS) {
+                        row(
+                            ui,
+                            &lang.trf(
+                                text!("Jours > {}"),
+                                &[&units.format(lang, Quantity::Rain, threshold)],
+                            ),
+                            lang.trf(text!("{} jours"), &[&count]),
+                        );
+                    }
+                });
+
+                card(ui, lang.tr(text!("Vent")), |ui| {
+                    row(
+                        ui,
+                        lang.tr(text!("Vitesse moyenne")),
+                        units.format(lang, Quantity::Wind, self.mean_wind),
+                    );
+                    if let Some((wind, at)) = self.high_wind {
+                        row(
+                            ui,
+                            lang.tr(text!("Rafale maximale")),
+                            lang.trf(
+                                text!("{} le {}"),
+                                &[
+                                    &units.format(lang, Quantity::Wind, wind),
+                                    &lang.date_time(at),
+                                ],
+                            ),
+                        );
+                    }
+                    let direction = self.dominant_direction.as_deref().unwrap_or("---");
+                    row(
+                        ui,
+                        lang.tr(text!("Direction dominante")),
+                        direction.to_string(),
+                    );
+                });
+            });
+        });
+    }
+}
+
+/// Replaces the current extreme if `better` says the new value beats it.
+fn keep_extreme<T>(
+    current: &mut Option<(f64, T)>,
+    value: f64,
+    at: T,
+    better: impl Fn(f64, f64) -> bool,
+) {
+    if current
+        .as_ref()
+        .is_none_or(|(extreme, _)| better(value, *extreme))
+    {
+        *current = Some((value, at));
+    }
+}
+
+fn card(ui: &mut Ui, title: &str, content: impl FnOnce(&mut Ui)) {
+    egui::Frame::group(ui.style()).show(ui, |ui| {
+        ui.vertical(|ui| {
+            ui.label(RichText::new(title).heading());
+            ui.separator();
+            Grid::new(title)
+                .num_columns(2)
+                .striped(true)
+                .show(ui, content);
+        });
+    });
+}
+
+fn row(ui: &mut Ui, name: &str, value: String) {
+    ui.label(name);
+    ui.label(RichText::new(value).strong());
+    ui.end_row();
+}
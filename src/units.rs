@@ -0,0 +1,288 @@
+use egui::{Context, Id, Ui};
+
+use crate::i18n::{text, Language};
+
+/// What a value of the reports measures, the reports are always in °C, mm and km/h.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Quantity {
+    Temperature,
+    /// A difference of temperatures, like an anomaly or degree-days, it's scaled but
+    /// not shifted.
+    TemperatureDifference,
+    Rain,
+    Wind,
+}
+
+#[derive(Default, Debug, PartialEq, Eq, Clone, Copy, serde::Serialize, serde::Deserialize)]
+pub enum Temperature {
+    #[default]
+    Celsius,
+    Fahrenheit,
+}
+
+#[derive(Default, Debug, PartialEq, Eq, Clone, Copy, serde::Serialize, serde::Deserialize)]
+pub enum Rain {
+    #[default]
+    Millimeter,
+    Inch,
+}
+
+#[derive(Default, Debug, PartialEq, Eq, Clone, Copy, serde::Serialize, serde::Deserialize)]
+pub enum Wind {
+    #[default]
+    KilometerPerHour,
+    MeterPerSecond,
+    Knot,
+    Beaufort,
+}
+
+/// The units the user wants the values in.
+///
+/// They're stored in the [`Context`] every frame so every view can get them
+/// without passing them around, like the theme.
+#[derive(Default, Debug, PartialEq, Eq, Clone, Copy, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+pub struct Units {
+    pub temperature: Temperature,
+    pub rain: Rain,
+    pub wind: Wind,
+}
+
+impl Units {
+    fn id() -> Id {
+        Id::new("units")
+    }
+
+    /// The units picked by the user.
+    pub fn get(ctx: &Context) -> Self {
+        ctx.data(|data| data.get_temp(Self::id()))
+            .unwrap_or_default()
+    }
+
+    pub fn store(self, ctx: &Context) {
+        ctx.data_mut(|data| data.insert_temp(Self::id(), self));
+    }
+
+    /// Converts a value of the reports for the plots, see [`Units::value`] for the
+    /// texts and tables.
+    pub fn convert(self, quantity: Quantity, value: f64) -> f64 {
+        match quantity {
+            Quantity::Temperature => match self.temperature {
+                Temperature::Celsius => value,
+                Temperature::Fahrenheit => value * 1.8 + 32.0,
+            },
+            Quantity::TemperatureDifference => match self.temperature {
+                Temperature::Celsius => value,
+                Temperature::Fahrenheit => value * 1.8,
+            },
+            Quantity::Rain => match self.rain {
+                Rain::Millimeter => value,
+                Rain::Inch => value / 25.4,
+            },
+            Quantity::Wind => match self.wind {
+                Wind::KilometerPerHour => value,
+                Wind::MeterPerSecond => value / 3.6,
+                Wind::Knot => value / 1.852,
+                // the empirical formula behind the scale, a continuous value plots better
+                // than the rounded force
+                Wind::Beaufort => (value / 3.6 / 0.836).powf(2.0 / 3.0),
+            },
+        }
+    }
+
+    /// Converts a value of the reports to show it as a number, the Beaufort scale
+    /// only has whole forces from 0 to 12.
+    pub fn value(self, quantity: Quantity, value: f64) -> f64 {
+        let value = self.convert(quantity, value);
+        match (quantity, self.wind) {
+            (Quantity::Wind, Wind::Beaufort) => value.round().min(12.0),
+            _ => value,
+        }
+    }
+
+    pub fn symbol(self, quantity: Quantity) -> &'static str {
+        match quantity {
+            Quantity::Temperature | Quantity::TemperatureDifference => match self.temperature {
+                Temperature::Celsius => "°C",
+                Temperature::Fahrenheit => "°F",
+            },
+            Quantity::Rain => match self.rain {
+                Rain::Millimeter => "mm",
+                Rain::Inch => "in",
+            },
+            Quantity::Wind => match self.wind {
+                Wind::KilometerPerHour => "km/h",
+                Wind::MeterPerSecond => "m/s",
+                Wind::Knot => "kn",
+                Wind::Beaufort => "Bft",
+            },
+        }
+    }
+
+    /// How many decimals are worth showing, a tenth of an inch of rain is already
+    /// a rainy day.
+    pub fn decimals(self, quantity: Quantity) -> usize {
+        match (quantity, self.rain, self.wind) {
+            (Quantity::Rain, Rain::Inch, _) => 2,
+            (Quantity::Wind, _, Wind::Beaufort) => 0,
+            _ => 1,
+        }
+    }
+
+    /// A value of the reports converted and followed by its unit, `lang` decides the
+    /// decimal separator.
+    pub fn format(self, lang: Language, quantity: Quantity, value: f64) -> String {
+        let value = self.value(quantity, value);
+        let number = lang.number(value, self.decimals(quantity));
+        format!("{number}{}", self.symbol(quantity))
+    }
+
+    /// A value the plots already converted followed by its unit, with one more
+    /// decimal than [`Units::format`] since the user is looking closely.
+    pub fn format_plotted(self, lang: Language, quantity: Quantity, value: f64) -> String {
+        let number = lang.number(value, self.decimals(quantity) + 1);
+        format!("{number}{}", self.symbol(quantity))
+    }
+
+    /// A threshold like the 32°C of the NOAA footer, without decimals.
+    pub fn format_round(self, lang: Language, quantity: Quantity, value: f64) -> String {
+        // adding zero turns the -0 of -18°C in °F into a 0
+        let value = self.value(quantity, value).round() + 0.0;
+        format!("{}{}", lang.number(value, 0), self.symbol(quantity))
+    }
+
+    /// Lets the user pick the units, in the top bar.
+    pub fn ui(&mut self, ui: &mut Ui) {
+        let lang = Language::get(ui.ctx());
+        ui.menu_button(lang.tr(text!("Unités")), |ui| {
+            ui.horizontal(|ui| {
+                ui.label(lang.tr(text!("Température :")));
+                ui.selectable_value(&mut self.temperature, Temperature::Celsius, "°C");
+                ui.selectable_value(&mut self.temperature, Temperature::Fahrenheit, "°F");
+            });
+            ui.horizontal(|ui| {
+                ui.label(lang.tr(text!("Pluie :")));
+                ui.selectable_value(&mut self.rain, Rain::Millimeter, "mm");
+                ui.selectable_value(&mut self.rain, Rain::Inch, lang.tr(text!("pouces")));
+            });
+            ui.horizontal(|ui| {
+                ui.label(lang.tr(text!("Vent :")));
+                ui.selectable_value(&mut self.wind, Wind::KilometerPerHour, "km/h");
+                ui.selectable_value(&mut self.wind, Wind::MeterPerSecond, "m/s");
+                ui.selectable_value(&mut self.wind, Wind::Knot, lang.tr(text!("nœuds")));
+                ui.selectable_value(&mut self.wind, Wind::Beaufort, "Beaufort");
+            });
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const IMPERIAL: Units = Units {
+        temperature: Temperature::Fahrenheit,
+        rain: Rain::Inch,
+        wind: Wind::Knot,
+    };
+
+    const BEAUFORT: Units = Units {
+        temperature: Temperature::Celsius,
+        rain: Rain::Millimeter,
+        wind: Wind::Beaufort,
+    };
+
+    fn assert_close(a: f64, b: f64) {
+        assert!((a - b).abs() < 1e-9, "{a} != {b}");
+    }
+
+    #[test]
+    fn convert() {
+        assert_close(IMPERIAL.convert(Quantity::Temperature, 0.0), 32.0);
+        assert_close(IMPERIAL.convert(Quantity::Temperature, 100.0), 212.0);
+        assert_close(
+            IMPERIAL.convert(Quantity::TemperatureDifference, 10.0),
+            18.0,
+        );
+        assert_close(IMPERIAL.convert(Quantity::Rain, 25.4), 1.0);
+        assert_close(IMPERIAL.convert(Quantity::Wind, 1.852), 1.0);
+        assert_close(Units::default().convert(Quantity::Temperature, 12.3), 12.3);
+    }
+
+    #[test]
+    fn beaufort() {
+        // the lowest speed of each force in km/h, from the official table
+        let forces = [
+            (0.0, 0.0),
+            (1.1, 1.0),
+            (5.6, 2.0),
+            (12.0, 3.0),
+            (20.0, 4.0),
+            (29.0, 5.0),
+            (39.0, 6.0),
+            (50.0, 7.0),
+            (62.0, 8.0),
+            (75.0, 9.0),
+            (89.0, 10.0),
+            (103.0, 11.0),
+            (118.0, 12.0),
+        ];
+        for (speed, force) in forces {
+            assert_eq!(BEAUFORT.value(Quantity::Wind, speed), force, "{speed} km/h");
+            // the table is in whole km/h
+            if force > 0.0 {
+                let below = BEAUFORT.value(Quantity::Wind, speed - 1.0);
+                assert_eq!(below, force - 1.0, "{} km/h", speed - 1.0);
+            }
+        }
+        assert_eq!(BEAUFORT.value(Quantity::Wind, 200.0), 12.0);
+        // the plots keep the continuous value
+        assert!(BEAUFORT.convert(Quantity::Wind, 200.0) > 12.0);
+        assert_eq!(
+            BEAUFORT.format(Language::French, Quantity::Wind, 30.0),
+            "5Bft"
+        );
+        assert_eq!(
+            BEAUFORT.format(Language::French, Quantity::Rain, 1.25),
+            "1,2mm"
+        );
+    }
+
+    #[test]
+    fn format() {
+        assert_eq!(
+            Units::default().format(Language::French, Quantity::Temperature, 12.3),
+            "12,3°C"
+        );
+        assert_eq!(
+            IMPERIAL.format(Language::English, Quantity::Rain, 25.4),
+            "1.00in"
+        );
+        assert_eq!(
+            IMPERIAL.format_plotted(Language::English, Quantity::Wind, 1.25),
+            "1.25kn"
+        );
+    }
+
+    #[test]
+    fn format_round() {
+        let celsius = Units::default();
+        assert_eq!(
+            celsius.format_round(Language::French, Quantity::Temperature, 32.0),
+            "32°C"
+        );
+        assert_eq!(
+            IMPERIAL.format_round(Language::English, Quantity::Temperature, 32.0),
+            "90°F"
+        );
+        // -17.8°C is 0°F, without the sign of a rounded -0.04
+        assert_eq!(
+            IMPERIAL.format_round(Language::English, Quantity::Temperature, -17.8),
+            "0°F"
+        );
+        assert_eq!(
+            IMPERIAL.format_round(Language::English, Quantity::Rain, 12.7),
+            "1in"
+        );
+    }
+}
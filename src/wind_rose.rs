@@ -0,0 +1,214 @@
+use std::{f32::consts::TAU, ops::RangeInclusive};
+
+use egui::{Align2, Color32, FontId, Pos2, RichText, Sense, Shape, Stroke, Ui, Vec2};
+use time::Date;
+
+use crate::{
+    i18n::{text, Language},
+    noaa,
+    units::{Quantity, Units},
+};
+
+/// The 16 directions of the compass as written in the DOM DIR column, clockwise
+/// starting from the north.
+const DIRECTIONS: [&str; 16] = [
+    "N", "NNE", "NE", "ENE", "E", "ESE", "SE", "SSE", "S", "SSW", "SW", "WSW", "W", "WNW", "NW",
+    "NNW",
+];
+
+/// Upper bound of each speed class in km/h, the last class has no upper bound.
+const SPEED_CLASSES: [f64; 4] = [10.0, 20.0, 30.0, 50.0];
+
+const CLASS_COLORS: [Color32; SPEED_CLASSES.len() + 1] = [
+    Color32::from_rgb(160, 210, 255),
+    Color32::from_rgb(90, 170, 240),
+    Color32::from_rgb(70, 190, 110),
+    Color32::from_rgb(240, 170, 50),
+    Color32::from_rgb(220, 60, 50),
+];
+
+/// Which speed is used to sort the days in the speed classes.
+#[derive(Default, Debug, PartialEq, Clone, Copy)]
+pub enum WindSpeed {
+    #[default]
+    High,
+    Average,
+}
+
+pub struct WindRose {
+    /// Number of days for each direction and each speed class.
+    counts: [[usize; SPEED_CLASSES.len() + 1]; DIRECTIONS.len()],
+    calm: usize,
+    total: usize,
+}
+
+impl WindRose {
+    pub fn new(report: &noaa::Report, period: RangeInclusive<Date>, speed: WindSpeed) -> Self {
+        let mut rose = Self {
+            counts: Default::default(),
+            calm: 0,
+            total: 0,
+        };
+
+        for day in report.days.iter().filter(|day| period.contains(&day.date)) {
+            rose.total += 1;
+            let direction = day.dom_dir.trim();
+            // `---` means the wind never picked a direction during the day
+            let Some(direction) = DIRECTIONS.iter().position(|dir| *dir == direction) else {
+                rose.calm += 1;
+                continue;
+            };
+            let speed = match speed {
+                WindSpeed::High => day.high_wind_speed as f64,
+                WindSpeed::Average => day.avg_wind_speed as f64,
+            };
+            let class = SPEED_CLASSES
+                .iter()
+                .position(|max| speed < *max)
+                .unwrap_or(SPEED_CLASSES.len());
+            rose.counts[direction][class] += 1;
+        }
+
+        rose
+    }
+
+    pub fn ui(&self, ui: &mut Ui) {
+        let lang = Language::get(ui.ctx());
+        let units = Units::get(ui.ctx());
+        ui.horizontal_wrapped(|ui| {
+            for (class, color) in CLASS_COLORS.iter().enumerate() {
+                ui.label(RichText::new("■").color(*color));
+                ui.label(class_name(class, units, lang));
+            }
+        });
+
+        if self.total == 0 {
+            ui.label(lang.tr(text!("Aucune donnée sur cette période")));
+            return;
+        }
+
+        let size = ui.available_size().min_elem();
+        let (response, painter) = ui.allocate_painter(Vec2::splat(size), Sense::hover());
+        let center = response.rect.center();
+        // keep some room around the rose for the name of the directions
+        let radius = (size / 2.0 - 20.0).max(10.0);
+        let biggest = self
+            .counts
+            .iter()
+            .map(|classes| classes.iter().sum::<usize>())
+            .max()
+            .unwrap_or(0)
+            .max(1);
+        let scale = radius / biggest as f32;
+
+        let grid = ui.visuals().widgets.noninteractive.bg_stroke;
+        let text_color = ui.visuals().text_color();
+
+        for step in 1..=4 {
+            let r = radius * step as f32 / 4.0;
+            painter.circle_stroke(center, r, grid);
+            let percent = biggest as f64 * step as f64 / 4.0 / self.total as f64 * 100.0;
+            painter.text(
+                center + Vec2::new(2.0, -r),
+                Align2::LEFT_BOTTOM,
+                format!("{}%", lang.number(percent, 0)),
+                FontId::proportional(10.0),
+                text_color,
+            );
+        }
+
+        for (direction, classes) in self.counts.iter().enumerate() {
+            let angle = direction_angle(direction);
+            painter.text(
+                center + polar(radius + 10.0, angle),
+                Align2::CENTER_CENTER,
+                DIRECTIONS[direction],
+                FontId::proportional(12.0),
+                text_color,
+            );
+
+            // Draw the stack from the outside so the fastest class is only visible
+            // where it sticks out of the slower ones.
+            let mut total: usize = classes.iter().sum();
+            for class in (0..classes.len()).rev() {
+                if total > 0 {
+                    painter.add(wedge(
+                        center,
+                        total as f32 * scale,
+                        angle,
+                        CLASS_COLORS[class],
+                    ));
+                }
+                total -= classes[class];
+            }
+        }
+
+        let calm = self.calm as f64 / self.total as f64 * 100.0;
+        painter.text(
+            center,
+            Align2::CENTER_CENTER,
+            lang.trf(text!("calme\n{}%"), &[&lang.number(calm, 0)]),
+            FontId::proportional(11.0),
+            text_color,
+        );
+
+        let Some(pointer) = response.hover_pos() else {
+            return;
+        };
+        let offset = pointer - center;
+        if offset.length() > radius {
+            return;
+        }
+        let angle = offset.x.atan2(-offset.y).rem_euclid(TAU);
+        let sector = TAU / DIRECTIONS.len() as f32;
+        let direction = ((angle + sector / 2.0) / sector) as usize % DIRECTIONS.len();
+        let classes = self.counts[direction];
+        response.on_hover_ui_at_pointer(|ui| {
+            let days: usize = classes.iter().sum();
+            ui.label(RichText::new(DIRECTIONS[direction]).strong());
+            ui.label(lang.trf(text!("{} jours sur {}"), &[&days, &self.total]));
+            for (class, count) in classes.iter().enumerate() {
+                if *count > 0 {
+                    ui.label(lang.trf(
+                        text!("{} : {} jours"),
+                        &[&class_name(class, units, lang), count],
+                    ));
+                }
+            }
+        });
+    }
+}
+
+fn class_name(class: usize, units: Units, lang: Language) -> String {
+    let speed = |speed: f64| units.format_round(lang, Quantity::Wind, speed);
+    match class {
+        0 => format!("< {}", speed(SPEED_CLASSES[0])),
+        class if class == SPEED_CLASSES.len() => format!("≥ {}", speed(SPEED_CLASSES[class - 1])),
+        class => format!(
+            "{} - {}",
+            speed(SPEED_CLASSES[class - 1]),
+            speed(SPEED_CLASSES[class])
+        ),
+    }
+}
+
+/// The angle of a direction in radians, clockwise from the north.
+fn direction_angle(direction: usize) -> f32 {
+    direction as f32 * TAU / DIRECTIONS.len() as f32
+}
+
+/// Converts a polar coordinate with the north at the top into a screen offset.
+fn polar(radius: f32, angle: f32) -> Vec2 {
+    Vec2::new(radius * angle.sin(), -radius * angle.cos())
+}
+
+/// A pie slice centered on `angle`, with a small gap with its neighbours.
+fn wedge(center: Pos2, radius: f32, angle: f32, color: Color32) -> Shape {
+    let half = TAU / DIRECTIONS.len() as f32 / 2.0 * 0.9;
+    let mut points = vec![center];
+    for step in 0..=8 {
+        let angle = angle - half + 2.0 * half * step as f32 / 8.0;
+        points.push(center + polar(radius, angle));
+    }
+    Shape::convex_polygon(points, color, Stroke::NONE)
+}